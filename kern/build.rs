@@ -1,3 +1,104 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Runs `cmd` with `args` and returns its trimmed stdout, if it succeeded.
+fn output(cmd: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(cmd).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The sources that go into the kernel image. A build is `-dirty` if any of
+/// them differ from the commit, and is redone whenever any of them change.
+const SOURCES: &[&str] = &[
+    "src",
+    "Cargo.toml",
+    "../lib/pi/src",
+    "../lib/shim/src",
+    "../lib/stack-vec/src",
+    "../lib/volatile/src",
+];
+
+/// Prints `rerun-if-changed` for the git files that change on a commit or a
+/// checkout, so that the exported commit hash never goes stale.
+fn watch_git() {
+    let mut paths = vec!["HEAD".to_string(), "index".to_string(), "packed-refs".to_string()];
+
+    // A commit on a branch updates the branch's ref rather than HEAD. If the
+    // ref is currently packed, the next commit creates the loose ref file, so
+    // watch the directory it will appear in.
+    if let Some(head_ref) = output("git", &["symbolic-ref", "-q", "HEAD"]) {
+        paths.push(match head_ref.rfind('/') {
+            Some(i) => head_ref[..i].to_string(),
+            None => head_ref.clone(),
+        });
+        paths.push(head_ref);
+    }
+
+    for path in paths {
+        if let Some(path) = output("git", &["rev-parse", "--git-path", &path]) {
+            if Path::new(&path).exists() {
+                println!("cargo:rerun-if-changed={}", path);
+            }
+        }
+    }
+}
+
+/// Prints `rerun-if-changed` for `path` and, if it is a directory, every file
+/// under it. Older versions of cargo only check a directory's own timestamp.
+fn watch(path: &Path) {
+    println!("cargo:rerun-if-changed={}", path.display());
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.filter_map(Result::ok) {
+            watch(&entry.path());
+        }
+    }
+}
+
+/// Exports the build information read by `kern::buildinfo`.
+fn build_info() {
+    let commit = output("git", &["rev-parse", "--short=12", "HEAD"]).unwrap_or_else(|| "unknown".into());
+    let mut status = vec!["status", "--porcelain", "--untracked-files=no", "--"];
+    status.extend(SOURCES);
+    let dirty = output("git", &status).map_or(false, |status| !status.is_empty());
+    let commit = if dirty { format!("{}-dirty", commit) } else { commit };
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    let rustc_version = output(&rustc, &["--version"]).unwrap_or_else(|| "unknown".into());
+
+    const FEATURE_PREFIX: &str = "CARGO_FEATURE_";
+    let mut features: Vec<String> = env::vars()
+        .map(|(key, _)| key)
+        .filter(|key| key.starts_with(FEATURE_PREFIX))
+        .map(|key| key[FEATURE_PREFIX.len()..].to_lowercase().replace('_', "-"))
+        .collect();
+    features.sort();
+
+    println!("cargo:rustc-env=KERN_GIT_COMMIT={}", commit);
+    println!("cargo:rustc-env=KERN_BUILD_TIMESTAMP={}", timestamp);
+    println!("cargo:rustc-env=KERN_RUSTC_VERSION={}", rustc_version);
+    println!("cargo:rustc-env=KERN_FEATURES={}", features.join(","));
+
+    // Rebuild on new commits and on source changes, which may change the
+    // `-dirty` suffix.
+    watch_git();
+    for source in SOURCES {
+        watch(Path::new(source));
+    }
+}
+
 pub fn main() {
     println!("cargo:rerun-if-changed=.cargo/layout.ld");
+    build_info();
 }
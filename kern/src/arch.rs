@@ -0,0 +1,116 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Bit of the virtual counter whose transitions generate an event stream
+/// event. The counter runs at 19.2MHz, so bit 10 fires roughly every 53us.
+const EVENT_STREAM_BIT: u64 = 10;
+
+/// Whether `idle()` waits for an event or busy-spins.
+static LOW_POWER: AtomicBool = AtomicBool::new(true);
+
+/// Enables or disables low-power idling. With low-power idling disabled,
+/// `idle()` returns immediately, which keeps spin loops cycle-accurate while
+/// debugging timing-sensitive code at the cost of power and heat. The kernel
+/// disables it at boot when built with the `spin_idle` feature.
+pub fn set_low_power(enabled: bool) {
+    LOW_POWER.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns `true` if `idle()` waits for an event.
+pub fn low_power() -> bool {
+    LOW_POWER.load(Ordering::Relaxed)
+}
+
+/// Enables the generic timer's event stream so that a core waiting in `idle()`
+/// is woken periodically even if no other core or interrupt sends an event.
+/// Must be called at EL1 before the first call to `idle()`.
+pub fn enable_event_stream() {
+    // CNTKCTL_EL1: EVNTI (bits 7:4) selects the counter bit, EVNTEN (bit 2)
+    // enables the stream (ref: D7.5.10).
+    let cntkctl = (EVENT_STREAM_BIT << 4) | (1 << 2);
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        asm!("msr CNTKCTL_EL1, $0
+              isb"
+             :
+             : "r"(cntkctl)
+             :
+             : "volatile");
+    }
+
+    #[cfg(not(target_arch = "aarch64"))]
+    let _ = cntkctl;
+}
+
+/// Idles the core for a short while in a low-power state. Intended for the
+/// body of spin loops: callers must re-check their condition after `idle()`
+/// returns, since it may return early or spuriously.
+///
+/// The core waits for an event (`wfe`), which is sent by `wake()` on any core,
+/// by an interrupt, or by the event stream set up by `enable_event_stream()`.
+#[inline(always)]
+pub fn idle() {
+    if low_power() {
+        #[cfg(target_arch = "aarch64")]
+        unsafe { asm!("wfe" :::: "volatile"); }
+    }
+}
+
+/// Sends an event to all cores, waking any that are in `idle()`. Should be
+/// called after releasing a resource other cores may be spinning on. Memory
+/// writes made before the call are visible to the woken cores.
+#[inline(always)]
+pub fn wake() {
+    // Without the barrier, a woken core may re-check its condition before the
+    // write that released it is visible and go back to waiting.
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        asm!("dsb ish
+              sev"
+             :
+             :
+             : "memory"
+             : "volatile");
+    }
+}
+
+/// Runs `f` with IRQs masked on this core, then restores the previous mask.
+///
+/// Locks shared with IRQ handlers must only be taken inside `f`: otherwise an
+/// IRQ arriving while the lock is held would have its handler wait forever on
+/// the code it interrupted.
+pub fn without_interrupts<R, F: FnOnce() -> R>(f: F) -> R {
+    let daif = mask_irqs();
+    let result = f();
+    restore_irqs(daif);
+    result
+}
+
+/// Masks IRQs, returning the previous value of `DAIF`.
+#[cfg(target_arch = "aarch64")]
+fn mask_irqs() -> u64 {
+    let daif: u64;
+    unsafe {
+        asm!("mrs $0, DAIF
+              msr DAIFSet, #2"
+             : "=r"(daif)
+             :
+             : "memory"
+             : "volatile");
+    }
+    daif
+}
+
+/// Restores `DAIF` to a value returned by `mask_irqs()`.
+#[cfg(target_arch = "aarch64")]
+fn restore_irqs(daif: u64) {
+    unsafe { asm!("msr DAIF, $0" : : "r"(daif) : "memory" : "volatile"); }
+}
+
+// Host builds (unit tests) have no IRQs to mask.
+#[cfg(not(target_arch = "aarch64"))]
+fn mask_irqs() -> u64 {
+    0
+}
+
+#[cfg(not(target_arch = "aarch64"))]
+fn restore_irqs(_daif: u64) {}
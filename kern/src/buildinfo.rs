@@ -0,0 +1,37 @@
+use core::fmt;
+
+/// The kernel crate version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The abbreviated git commit the kernel was built from, suffixed with
+/// `-dirty` if the kernel's sources or the libraries it is built from had
+/// uncommitted changes, or `unknown`.
+pub const GIT_COMMIT: &str = env!("KERN_GIT_COMMIT");
+
+/// The time the kernel was built, in seconds since the Unix epoch.
+pub const BUILD_TIMESTAMP: &str = env!("KERN_BUILD_TIMESTAMP");
+
+/// The `rustc --version` of the compiler that built the kernel.
+pub const RUSTC_VERSION: &str = env!("KERN_RUSTC_VERSION");
+
+/// Comma-separated list of the cargo features the kernel was built with.
+pub const FEATURES: &str = env!("KERN_FEATURES");
+
+/// Formats the build information as a one-line banner, e.g.
+///
+/// ```text
+/// kernel 0.1.0 (3f2a9c1d0e4b, built 1571234567) rustc 1.40.0-nightly [features: none]
+/// ```
+///
+/// Printed at boot and in panic dumps so that logs can be attributed to a
+/// specific image.
+#[derive(Debug, Copy, Clone)]
+pub struct Banner;
+
+impl fmt::Display for Banner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let features = if FEATURES.is_empty() { "none" } else { FEATURES };
+        write!(f, "kernel {} ({}, built {}) {} [features: {}]",
+               VERSION, GIT_COMMIT, BUILD_TIMESTAMP, RUSTC_VERSION, features)
+    }
+}
@@ -1,8 +1,10 @@
 use core::fmt;
+use core::sync::atomic::{AtomicUsize, Ordering};
 use pi::uart::MiniUart;
 use shim::io;
 
 use crate::mutex::Mutex;
+use crate::timefmt::{self, Timestamp};
 
 /// A global singleton allowing read/write access to the console.
 pub struct Console {
@@ -60,9 +62,39 @@ impl fmt::Write for Console {
     }
 }
 
+/// Writes the pre-rendered `bytes` straight to the UART, bypassing `CONSOLE`
+/// and its lock. For exception handlers and fault paths only, where taking the
+/// console lock could deadlock; everything else should use `kprint!`.
+pub fn emergency_write(bytes: &[u8]) {
+    pi::uart::emergency_write(bytes)
+}
+
 /// Global `Console` singleton.
 pub static CONSOLE: Mutex<Console> = Mutex::new(Console::new());
 
+/// Number of rows of the attached terminal. Defaults to 24.
+static ROWS: AtomicUsize = AtomicUsize::new(24);
+
+/// Number of columns of the attached terminal. Defaults to 80.
+static COLUMNS: AtomicUsize = AtomicUsize::new(80);
+
+/// Sets the size of the attached terminal to `rows` x `columns`. The serial
+/// line has no way to query this, so it must be set by the user.
+///
+/// # Panics
+///
+/// Panics if `rows` or `columns` is zero.
+pub fn set_size(rows: usize, columns: usize) {
+    assert!(rows > 0 && columns > 0, "console size must be non-zero");
+    ROWS.store(rows, Ordering::Relaxed);
+    COLUMNS.store(columns, Ordering::Relaxed);
+}
+
+/// Returns the size of the attached terminal as `(rows, columns)`.
+pub fn size() -> (usize, usize) {
+    (ROWS.load(Ordering::Relaxed), COLUMNS.load(Ordering::Relaxed))
+}
+
 /// Internal function called by the `kprint[ln]!` macros.
 #[doc(hidden)]
 pub fn _print(args: fmt::Arguments) {
@@ -79,11 +111,22 @@ pub fn _print(args: fmt::Arguments) {
     }
 }
 
+/// Internal function called by the `kprintln!` macro. Prefixes the line with
+/// a timestamp if enabled via `timefmt::set_timestamps()`.
+#[doc(hidden)]
+pub fn _println(args: fmt::Arguments) {
+    if timefmt::timestamps() {
+        _print(format_args!("{} {}\n", Timestamp::now(), args));
+    } else {
+        _print(format_args!("{}\n", args));
+    }
+}
+
 /// Like `println!`, but for kernel-space.
 pub macro kprintln {
-    () => (kprint!("\n")),
-    ($fmt:expr) => (kprint!(concat!($fmt, "\n"))),
-    ($fmt:expr, $($arg:tt)*) => (kprint!(concat!($fmt, "\n"), $($arg)*))
+    () => (_println(format_args!(""))),
+    ($fmt:expr) => (_println(format_args!($fmt))),
+    ($fmt:expr, $($arg:tt)*) => (_println(format_args!($fmt, $($arg)*)))
 }
 
 /// Like `print!`, but for kernel-space.
@@ -4,6 +4,7 @@ use core::ptr::write_volatile;
 mod panic;
 mod oom;
 
+use crate::arch;
 use crate::kmain;
 
 global_asm!(include_str!("init/init.s"));
@@ -26,5 +27,8 @@ unsafe fn zeros_bss() {
 #[no_mangle]
 unsafe fn kinit() -> ! {
     zeros_bss();
+    arch::set_low_power(!cfg!(feature = "spin_idle"));
+    arch::enable_event_stream();
+    shim::time::set_clock(pi::timer::current_time);
     kmain();
 }
\ No newline at end of file
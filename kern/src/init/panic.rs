@@ -1,6 +1,19 @@
+use core::fmt::Write;
 use core::panic::PanicInfo;
 
+use stack_vec::StackString;
+
+use crate::buildinfo::Banner;
+use crate::console;
+
 #[panic_handler]
-fn panic(_info: &PanicInfo) -> ! {
+fn panic(info: &PanicInfo) -> ! {
+    // Render the dump on the stack and bypass the console lock, which the
+    // panicking code may hold. A dump too long for the buffer is cut short.
+    let mut buf = [0u8; 1024];
+    let mut dump = StackString::new(&mut buf);
+    let _ = write!(dump, "\n---------- PANIC ----------\n{}\n{}\n", Banner, info);
+    console::emergency_write(dump.as_bytes());
+
     loop {}
 }
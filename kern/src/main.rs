@@ -9,10 +9,19 @@
 
 #[cfg(not(test))]
 mod init;
+#[cfg(test)]
+mod tests;
 
+pub mod arch;
+pub mod buildinfo;
 pub mod console;
 pub mod mutex;
+pub mod pager;
 pub mod shell;
+pub mod sync;
+pub mod timefmt;
+pub mod timer;
+pub mod workqueue;
 
 use console::kprintln;
 
@@ -20,6 +29,8 @@ use console::kprintln;
 // test your drivers (Phase 2). Add them as needed.
 
 fn kmain() -> ! {
+    kprintln!("{}", buildinfo::Banner);
+
     // FIXME: Start the shell.
     unimplemented!()
 }
@@ -3,6 +3,8 @@ use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use core::cell::UnsafeCell;
 use core::ops::{DerefMut, Deref, Drop};
 
+use crate::arch;
+
 #[repr(align(32))]
 pub struct Mutex<T> {
     data: UnsafeCell<T>,
@@ -52,13 +54,14 @@ impl<T> Mutex<T> {
         loop {
             match self.try_lock() {
                 Some(guard) => return guard,
-                None => continue
+                None => arch::idle()
             }
         }
     }
 
     fn unlock(&self) {
         self.lock.store(false, Ordering::Relaxed);
+        arch::wake();
     }
 }
 
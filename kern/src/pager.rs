@@ -0,0 +1,112 @@
+use core::fmt::{self, Write};
+
+use crate::console::{self, CONSOLE};
+
+/// The prompt shown after each screenful of output.
+const PROMPT: &str = "--More-- (space: page, enter: line, q: quit)";
+
+/// A `fmt::Write` sink that pauses after each screenful of output.
+///
+/// The screen size is taken from `console::size()` when the pager is created.
+/// After `rows - 1` lines have been written, a prompt is shown and the pager
+/// blocks on console input:
+///
+///   * `space` shows the next screenful
+///   * `enter` shows one more line
+///   * `q` discards all remaining output
+///
+/// Long commands should check `quit()` periodically and stop early once the
+/// user has asked to quit.
+pub struct Pager {
+    rows: usize,
+    columns: usize,
+    /// Lines that may still be written before prompting.
+    remaining: usize,
+    /// Column of the cursor on the current line.
+    column: usize,
+    /// Whether the last character filled the line. The terminal has wrapped
+    /// onto the next line, so a `\n` right after it does not start another.
+    wrapped: bool,
+    quit: bool,
+}
+
+impl Pager {
+    /// Returns a new `Pager` sized to the current console size.
+    pub fn new() -> Pager {
+        let (rows, columns) = console::size();
+        let rows = rows.max(2);
+        Pager { rows, columns, remaining: rows - 1, column: 0, wrapped: false, quit: false }
+    }
+
+    /// Returns `true` if the user asked to stop paging. All output written
+    /// after this point is discarded.
+    pub fn quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Shows the prompt and waits for the user's choice.
+    fn prompt(&mut self) {
+        let mut console = CONSOLE.lock();
+        for byte in PROMPT.bytes() {
+            console.write_byte(byte);
+        }
+
+        self.remaining = loop {
+            match console.read_byte() {
+                b' ' => break self.rows - 1,
+                b'\r' | b'\n' => break 1,
+                b'q' | b'Q' => {
+                    self.quit = true;
+                    break 0;
+                }
+                _ => continue,
+            }
+        };
+
+        // Erase the prompt before continuing.
+        console.write_byte(b'\r');
+        for _ in 0..PROMPT.len() {
+            console.write_byte(b' ');
+        }
+        console.write_byte(b'\r');
+    }
+
+    /// Accounts for the end of a line, prompting if the screen is now full.
+    fn end_line(&mut self) {
+        self.column = 0;
+        self.remaining -= 1;
+        if self.remaining == 0 {
+            self.prompt();
+        }
+    }
+}
+
+impl fmt::Write for Pager {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if self.quit {
+                return Ok(());
+            }
+
+            let mut buf = [0u8; 4];
+            CONSOLE.lock().write_str(c.encode_utf8(&mut buf))?;
+
+            match c {
+                '\n' if self.wrapped => self.wrapped = false,
+                '\n' => self.end_line(),
+                // A `\r` between a wrap and its `\n` does not undo the wrap.
+                '\r' => self.column = 0,
+                _ => {
+                    self.wrapped = false;
+                    self.column += 1;
+                    if self.column == self.columns {
+                        self.end_line();
+                        self.wrapped = true;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
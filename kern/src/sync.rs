@@ -0,0 +1,96 @@
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::ptr;
+use core::sync::atomic::{self, AtomicUsize, Ordering};
+
+use crate::arch;
+
+/// A sequence lock: a cell for small `Copy` data that is read far more often
+/// than it is written.
+///
+/// Readers never block writers and never write to shared memory, so frequent
+/// readers do not contend with each other. A reader copies the value out and
+/// retries if a write happened in the meantime. Writers are serialized with
+/// each other and must be short, since readers wait while a write is in
+/// progress.
+///
+/// Unlike `Mutex`, a `SeqLock` never hands out references to its data, only
+/// copies.
+pub struct SeqLock<T: Copy> {
+    /// Even while no write is in progress, odd during a write. Incremented at
+    /// the start and at the end of every write.
+    seq: AtomicUsize,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: Copy + Send> Send for SeqLock<T> {}
+unsafe impl<T: Copy + Send> Sync for SeqLock<T> {}
+
+impl<T: Copy> SeqLock<T> {
+    /// Returns a new `SeqLock` holding `val`.
+    pub const fn new(val: T) -> SeqLock<T> {
+        SeqLock { seq: AtomicUsize::new(0), data: UnsafeCell::new(val) }
+    }
+
+    /// Returns a copy of the current value.
+    pub fn read(&self) -> T {
+        loop {
+            let start = self.seq.load(Ordering::Acquire);
+            if start & 1 == 1 {
+                arch::idle();
+                continue;
+            }
+
+            // A concurrent write may tear this copy; it is discarded below if
+            // the sequence number changed.
+            let value = unsafe { ptr::read_volatile(self.data.get()) };
+            atomic::fence(Ordering::Acquire);
+
+            if self.seq.load(Ordering::Relaxed) == start {
+                return value;
+            }
+        }
+    }
+
+    /// Replaces the current value with `val`.
+    pub fn write(&self, val: T) {
+        self.update(|_| val)
+    }
+
+    /// Replaces the current value with `f(current)`. Other writers are
+    /// excluded for the duration of the call, so `f` should be short.
+    pub fn update<F: FnOnce(T) -> T>(&self, f: F) {
+        let seq = self.lock();
+        unsafe {
+            let old = ptr::read_volatile(self.data.get());
+            ptr::write_volatile(self.data.get(), f(old));
+        }
+
+        self.seq.store(seq.wrapping_add(2), Ordering::Release);
+        arch::wake();
+    }
+
+    /// Waits for any other writer to finish, then marks a write as in
+    /// progress. Returns the (even) sequence number from before the write.
+    fn lock(&self) -> usize {
+        loop {
+            let seq = self.seq.load(Ordering::Relaxed);
+            if seq & 1 == 1 {
+                arch::idle();
+                continue;
+            }
+
+            if self.seq.compare_exchange_weak(seq, seq + 1, Ordering::Acquire, Ordering::Relaxed).is_ok() {
+                // Keep the data writes after the odd sequence number is visible.
+                atomic::fence(Ordering::Release);
+                return seq;
+            }
+        }
+    }
+}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for SeqLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SeqLock").field("data", &self.read()).finish()
+    }
+}
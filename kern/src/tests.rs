@@ -0,0 +1,164 @@
+mod workqueue {
+    use std::cell::RefCell;
+
+    use crate::workqueue::{Priority, Work, WorkQueue, QUEUE_LEN};
+
+    thread_local! {
+        static RAN: RefCell<Vec<usize>> = RefCell::new(Vec::new());
+    }
+
+    fn record(arg: usize) {
+        RAN.with(|ran| ran.borrow_mut().push(arg));
+    }
+
+    /// Runs every pending item of `queue`, returning their arguments in the
+    /// order they ran.
+    fn run_all(queue: &mut WorkQueue) -> Vec<usize> {
+        while let Some(work) = queue.next() {
+            work.run();
+        }
+
+        RAN.with(|ran| ran.replace(Vec::new()))
+    }
+
+    #[test]
+    fn priority_order() {
+        let mut queue = WorkQueue::new();
+        assert!(queue.enqueue(Priority::Low, Work::new(record, 5)).is_ok());
+        assert!(queue.enqueue(Priority::Normal, Work::new(record, 3)).is_ok());
+        assert!(queue.enqueue(Priority::High, Work::new(record, 1)).is_ok());
+        assert!(queue.enqueue(Priority::Normal, Work::new(record, 4)).is_ok());
+        assert!(queue.enqueue(Priority::High, Work::new(record, 2)).is_ok());
+        assert_eq!(queue.pending(), 5);
+
+        assert_eq!(run_all(&mut queue), vec![1, 2, 3, 4, 5]);
+        assert_eq!(queue.pending(), 0);
+        assert_eq!(queue.stats(Priority::High).executed, 2);
+        assert_eq!(queue.stats(Priority::Normal).executed, 2);
+        assert_eq!(queue.stats(Priority::Low).executed, 1);
+    }
+
+    #[test]
+    fn full_queue() {
+        let mut queue = WorkQueue::new();
+        for i in 0..QUEUE_LEN {
+            assert!(queue.enqueue(Priority::Normal, Work::new(record, i)).is_ok());
+        }
+
+        // The rejected item is handed back intact.
+        match queue.enqueue(Priority::Normal, Work::new(record, 100)) {
+            Ok(()) => panic!("enqueued into a full queue"),
+            Err(work) => work.run(),
+        }
+        assert_eq!(RAN.with(|ran| ran.replace(Vec::new())), vec![100]);
+
+        // Other priority levels have their own space.
+        assert!(queue.enqueue(Priority::High, Work::new(record, 200)).is_ok());
+
+        let stats = queue.stats(Priority::Normal);
+        assert_eq!(stats.enqueued, QUEUE_LEN as u64);
+        assert_eq!(stats.dropped, 1);
+        assert_eq!(stats.high_water, QUEUE_LEN);
+
+        let ran = run_all(&mut queue);
+        assert_eq!(ran[0], 200);
+        assert_eq!(&ran[1..], &(0..QUEUE_LEN).collect::<Vec<_>>()[..]);
+
+        // Space is reclaimed once items run.
+        assert!(queue.enqueue(Priority::Normal, Work::new(record, 0)).is_ok());
+    }
+}
+
+mod timer {
+    use std::cell::RefCell;
+    use std::time::Duration;
+
+    use crate::timer::TimerWheel;
+    use crate::workqueue::Work;
+
+    thread_local! {
+        static RAN: RefCell<Vec<usize>> = RefCell::new(Vec::new());
+    }
+
+    fn record(arg: usize) {
+        RAN.with(|ran| ran.borrow_mut().push(arg));
+    }
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    /// Runs every timer of `wheel` that is due at `now`, returning their
+    /// arguments in the order they ran.
+    fn expire_all(wheel: &mut TimerWheel, now: Duration) -> Vec<usize> {
+        while let Some(work) = wheel.expire(now) {
+            work.run();
+        }
+
+        RAN.with(|ran| ran.replace(Vec::new()))
+    }
+
+    #[test]
+    fn deadline_order() {
+        let mut wheel = TimerWheel::new();
+        assert!(wheel.schedule(ms(5), Work::new(record, 5)).is_ok());
+        assert!(wheel.schedule(ms(1), Work::new(record, 1)).is_ok());
+        assert!(wheel.schedule(ms(3), Work::new(record, 3)).is_ok());
+        assert_eq!(wheel.next_deadline(), Some(ms(1)));
+
+        assert_eq!(expire_all(&mut wheel, ms(0)), vec![]);
+        assert_eq!(expire_all(&mut wheel, ms(3)), vec![1, 3]);
+        assert_eq!(wheel.next_deadline(), Some(ms(5)));
+        assert_eq!(expire_all(&mut wheel, ms(10)), vec![5]);
+        assert_eq!(wheel.pending(), 0);
+    }
+
+    #[test]
+    fn later_turn_of_the_wheel() {
+        // 1ms and 65ms hash into the same slot.
+        let mut wheel = TimerWheel::new();
+        assert!(wheel.schedule(ms(65), Work::new(record, 65)).is_ok());
+        assert!(wheel.schedule(ms(1), Work::new(record, 1)).is_ok());
+
+        assert_eq!(expire_all(&mut wheel, ms(2)), vec![1]);
+        assert_eq!(expire_all(&mut wheel, ms(64)), vec![]);
+        assert_eq!(expire_all(&mut wheel, ms(65)), vec![65]);
+    }
+
+    #[test]
+    fn cancel() {
+        let mut wheel = TimerWheel::new();
+        let first = wheel.schedule(ms(2), Work::new(record, 2)).ok().unwrap();
+        let second = wheel.schedule(ms(4), Work::new(record, 4)).ok().unwrap();
+
+        assert!(wheel.cancel(first));
+        assert!(!wheel.cancel(first));
+        assert_eq!(wheel.pending(), 1);
+        assert_eq!(expire_all(&mut wheel, ms(10)), vec![4]);
+
+        // An expired timer cannot be cancelled, even once its entry is reused.
+        assert!(!wheel.cancel(second));
+        assert!(wheel.schedule(ms(20), Work::new(record, 20)).is_ok());
+        assert!(!wheel.cancel(second));
+        assert_eq!(wheel.pending(), 1);
+    }
+
+    #[test]
+    fn past_deadlines() {
+        let mut wheel = TimerWheel::new();
+        assert_eq!(expire_all(&mut wheel, ms(100)), vec![]);
+
+        // Deadlines the wheel has already moved past expire on the next call.
+        assert!(wheel.schedule(ms(20), Work::new(record, 20)).is_ok());
+        assert!(wheel.schedule(ms(100), Work::new(record, 100)).is_ok());
+        let mut ran = expire_all(&mut wheel, ms(100));
+        ran.sort();
+        assert_eq!(ran, vec![20, 100]);
+
+        // They can be cancelled like any other timer.
+        let past = wheel.schedule(ms(30), Work::new(record, 30)).ok().unwrap();
+        assert!(wheel.cancel(past));
+        assert_eq!(expire_all(&mut wheel, ms(200)), vec![]);
+        assert_eq!(wheel.pending(), 0);
+    }
+}
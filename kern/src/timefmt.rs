@@ -0,0 +1,87 @@
+use core::fmt;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+
+use pi::timer;
+
+use crate::sync::SeqLock;
+
+/// Whether `kprintln!` prefixes each line with a `Timestamp`.
+static TIMESTAMPS: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables `Timestamp` prefixes on `kprintln!` output.
+pub fn set_timestamps(enabled: bool) {
+    TIMESTAMPS.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns `true` if `kprintln!` output is prefixed with a `Timestamp`.
+pub fn timestamps() -> bool {
+    TIMESTAMPS.load(Ordering::Relaxed)
+}
+
+/// Time since the Unix epoch at which `timer::current_time()` was zero, once
+/// the time of day is known. Read on every `time_of_day()` call, written only
+/// when the clock is set.
+static BOOT_TIME: SeqLock<Option<Duration>> = SeqLock::new(None);
+
+/// Sets the time of day to `now`, the time since the Unix epoch.
+pub fn set_time_of_day(now: Duration) {
+    let uptime = timer::current_time();
+    BOOT_TIME.write(Some(now.checked_sub(uptime).unwrap_or_default()));
+}
+
+/// Returns the time since the Unix epoch, or `None` if the time of day has
+/// not been set with `set_time_of_day()`.
+pub fn time_of_day() -> Option<Duration> {
+    BOOT_TIME.read().map(|boot_time| boot_time + timer::current_time())
+}
+
+/// Formats a duration as seconds with microsecond precision, e.g. `12.345678`.
+#[derive(Debug, Copy, Clone)]
+pub struct Seconds(pub Duration);
+
+impl fmt::Display for Seconds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{:06}", self.0.as_secs(), self.0.subsec_micros())
+    }
+}
+
+/// Formats a time since boot as a fixed-width log prefix, e.g.
+/// `[   12.345678]`. Lines from different subsystems carrying this prefix can
+/// be correlated and sorted.
+#[derive(Debug, Copy, Clone)]
+pub struct Timestamp(pub Duration);
+
+impl Timestamp {
+    /// Returns a `Timestamp` for the current time.
+    pub fn now() -> Timestamp {
+        Timestamp(timer::current_time())
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{:>5}.{:06}]", self.0.as_secs(), self.0.subsec_micros())
+    }
+}
+
+/// Formats a duration compactly in the largest fitting unit, e.g. `1.500s`,
+/// `12.250ms`, `37us` or `800ns`. Intended for elapsed times.
+#[derive(Debug, Copy, Clone)]
+pub struct Compact(pub Duration);
+
+impl fmt::Display for Compact {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let d = self.0;
+        if d.as_secs() > 0 {
+            write!(f, "{}.{:03}s", d.as_secs(), d.subsec_millis())
+        } else if d.subsec_millis() > 0 {
+            write!(f, "{}.{:03}ms", d.subsec_millis(), d.subsec_micros() % 1000)
+        } else if d.subsec_micros() > 0 {
+            write!(f, "{}us", d.subsec_micros())
+        } else {
+            write!(f, "{}ns", d.subsec_nanos())
+        }
+    }
+}
+
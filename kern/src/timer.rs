@@ -0,0 +1,278 @@
+use core::time::Duration;
+
+use pi::timer::{self, Timer};
+
+use crate::arch;
+use crate::mutex::Mutex;
+use crate::workqueue::Work;
+
+/// Maximum number of pending software timers.
+const MAX_TIMERS: usize = 32;
+
+/// The number of slots in the wheel.
+const WHEEL_SLOTS: usize = 64;
+
+/// The time covered by a single slot, in microseconds.
+const TICK_US: u64 = 1000;
+
+/// The least time ahead of now that the hardware compare channel is set to.
+/// The channel only matches on equality, so a deadline that has already
+/// passed, or passes while it is being armed, would otherwise never fire.
+const MIN_DELTA: Duration = Duration::from_micros(10);
+
+/// Returns the wheel tick that `time` falls in.
+fn tick_of(time: Duration) -> u64 {
+    time.as_micros() as u64 / TICK_US
+}
+
+/// Identifies a scheduled timer so that it can be cancelled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TimerId {
+    index: usize,
+    generation: u32,
+}
+
+/// A scheduled timer.
+#[derive(Copy, Clone)]
+struct Pending {
+    deadline: Duration,
+    work: Work,
+    /// The next timer in the same slot.
+    next: Option<usize>,
+}
+
+/// An entry in the timer pool. `generation` is bumped every time the entry is
+/// freed so that stale `TimerId`s are not mistaken for a new timer.
+#[derive(Copy, Clone)]
+struct Entry {
+    pending: Option<Pending>,
+    generation: u32,
+}
+
+/// A hashed timer wheel multiplexing many software timers onto one hardware
+/// compare channel.
+///
+/// Timers are hashed into `WHEEL_SLOTS` slots by the tick their deadline falls
+/// in. Expiring timers only scans the slots for the ticks that have passed
+/// since the last expiry, plus timers in those slots that are due on a later
+/// turn of the wheel.
+pub struct TimerWheel {
+    entries: [Entry; MAX_TIMERS],
+    /// The first timer in each slot.
+    slots: [Option<usize>; WHEEL_SLOTS],
+    /// The first tick that has not been fully expired.
+    current_tick: u64,
+    len: usize,
+}
+
+impl TimerWheel {
+    /// Returns a new, empty `TimerWheel`.
+    pub const fn new() -> TimerWheel {
+        TimerWheel {
+            entries: [Entry { pending: None, generation: 0 }; MAX_TIMERS],
+            slots: [None; WHEEL_SLOTS],
+            current_tick: 0,
+            len: 0,
+        }
+    }
+
+    /// Schedules `work` to run once the time is `deadline` or later. A
+    /// deadline in the past expires on the next call to `expire()`.
+    ///
+    /// # Errors
+    ///
+    /// If `MAX_TIMERS` timers are already pending, `work` is returned as an
+    /// `Err`.
+    pub fn schedule(&mut self, deadline: Duration, work: Work) -> Result<TimerId, Work> {
+        let index = match self.entries.iter().position(|e| e.pending.is_none()) {
+            Some(index) => index,
+            None => return Err(work),
+        };
+
+        let slot = self.slot_of(deadline);
+        let entry = &mut self.entries[index];
+        entry.pending = Some(Pending { deadline, work, next: self.slots[slot] });
+        self.slots[slot] = Some(index);
+        self.len += 1;
+
+        Ok(TimerId { index, generation: entry.generation })
+    }
+
+    /// Returns the slot a timer due at `deadline` is hashed into. Deadlines
+    /// that have already been expired past go in the current tick's slot.
+    fn slot_of(&self, deadline: Duration) -> usize {
+        (tick_of(deadline).max(self.current_tick) % WHEEL_SLOTS as u64) as usize
+    }
+
+    /// Cancels the timer `id`. Returns `true` if the timer was pending, and
+    /// `false` if it already expired or was cancelled.
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        let entry = &self.entries[id.index];
+        match entry.pending {
+            Some(pending) if entry.generation == id.generation => {
+                let slot = self.slot_of(pending.deadline);
+                // A timer scheduled in the past may have been hashed by an
+                // older `current_tick`, so search every slot if need be.
+                let found = self.unlink(slot, id.index)
+                    || (0..WHEEL_SLOTS).any(|slot| self.unlink(slot, id.index));
+                debug_assert!(found, "pending timer is not in the wheel");
+                self.free(id.index);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes timer `index` from the list of slot `slot`. Returns `true` if
+    /// it was found.
+    fn unlink(&mut self, slot: usize, index: usize) -> bool {
+        let mut prev: Option<usize> = None;
+        let mut cursor = self.slots[slot];
+        while let Some(i) = cursor {
+            let next = self.entries[i].pending.and_then(|p| p.next);
+            if i == index {
+                match prev {
+                    Some(prev) => self.entries[prev].pending.as_mut().unwrap().next = next,
+                    None => self.slots[slot] = next,
+                }
+
+                return true;
+            }
+
+            prev = Some(i);
+            cursor = next;
+        }
+
+        false
+    }
+
+    /// Frees entry `index`, returning its work.
+    fn free(&mut self, index: usize) -> Option<Work> {
+        let entry = &mut self.entries[index];
+        let pending = entry.pending.take()?;
+        entry.generation = entry.generation.wrapping_add(1);
+        self.len -= 1;
+        Some(pending.work)
+    }
+
+    /// Removes and returns the work of one timer whose deadline is at or
+    /// before `now`, if any.
+    pub fn expire(&mut self, now: Duration) -> Option<Work> {
+        let now_tick = tick_of(now);
+
+        // Visiting more than one turn of the wheel would scan slots twice.
+        if now_tick >= self.current_tick + WHEEL_SLOTS as u64 {
+            self.current_tick = now_tick + 1 - WHEEL_SLOTS as u64;
+        }
+
+        while self.current_tick <= now_tick {
+            let slot = (self.current_tick % WHEEL_SLOTS as u64) as usize;
+            let mut cursor = self.slots[slot];
+            while let Some(i) = cursor {
+                let pending = self.entries[i].pending.expect("slot lists only hold pending timers");
+                if pending.deadline <= now {
+                    self.unlink(slot, i);
+                    return self.free(i);
+                }
+
+                cursor = pending.next;
+            }
+
+            // Timers in the current tick may still be due later in the tick.
+            if self.current_tick == now_tick {
+                break;
+            }
+
+            self.current_tick += 1;
+        }
+
+        None
+    }
+
+    /// Returns the earliest deadline of any pending timer.
+    pub fn next_deadline(&self) -> Option<Duration> {
+        self.entries.iter().filter_map(|e| e.pending).map(|p| p.deadline).min()
+    }
+
+    /// Returns the number of pending timers.
+    pub fn pending(&self) -> usize {
+        self.len
+    }
+}
+
+/// Global software timers. Locked with IRQs masked, since `run_expired()`
+/// takes the lock from the timer interrupt; use the functions below rather
+/// than locking it directly.
+static TIMERS: Mutex<TimerWheel> = Mutex::new(TimerWheel::new());
+
+/// Schedules `func(arg)` to be run by `run_expired()` once the time since boot
+/// is `deadline` or later. A deadline that has already passed runs on the
+/// next timer interrupt, at most `MIN_DELTA` from now.
+///
+/// # Errors
+///
+/// Returns `Err(())` if `MAX_TIMERS` timers are already pending.
+pub fn schedule_at(deadline: Duration, func: fn(usize), arg: usize) -> Result<TimerId, ()> {
+    arch::without_interrupts(|| {
+        let id = TIMERS.lock().schedule(deadline, Work::new(func, arg)).map_err(|_| ())?;
+        rearm();
+        Ok(id)
+    })
+}
+
+/// Schedules `func(arg)` to be run by `run_expired()` after `delay`.
+///
+/// # Errors
+///
+/// Returns `Err(())` if `MAX_TIMERS` timers are already pending.
+pub fn schedule_in(delay: Duration, func: fn(usize), arg: usize) -> Result<TimerId, ()> {
+    schedule_at(timer::current_time() + delay, func, arg)
+}
+
+/// Cancels the timer `id`. Returns `true` if the timer was still pending.
+pub fn cancel(id: TimerId) -> bool {
+    arch::without_interrupts(|| TIMERS.lock().cancel(id))
+}
+
+/// Returns the number of pending global timers.
+pub fn pending() -> usize {
+    arch::without_interrupts(|| TIMERS.lock().pending())
+}
+
+/// Runs every timer that has expired, then sets the hardware compare channel
+/// to match at the next pending deadline. Returns the number of timers that
+/// were run.
+///
+/// This is meant to be called from the system timer interrupt. The lock is not
+/// held while a timer runs, so timers may schedule more timers.
+pub fn run_expired() -> usize {
+    let now = timer::current_time();
+    let mut ran = 0;
+    loop {
+        let work = match arch::without_interrupts(|| TIMERS.lock().expire(now)) {
+            Some(work) => work,
+            None => break,
+        };
+
+        work.run();
+        ran += 1;
+    }
+
+    arch::without_interrupts(rearm);
+    ran
+}
+
+/// Clears any pending match on the hardware compare channel, then sets it to
+/// match at the earliest pending deadline, or `MIN_DELTA` from now if that is
+/// later. With no pending timers the channel is left unarmed, so a handled
+/// interrupt is not raised again. Must be called with IRQs masked so that the
+/// channel is armed before `now + MIN_DELTA` passes.
+fn rearm() {
+    let deadline = TIMERS.lock().next_deadline();
+    let mut hw = Timer::new();
+    hw.clear_match();
+    if let Some(deadline) = deadline {
+        let earliest = timer::current_time() + MIN_DELTA;
+        hw.set_compare(deadline.max(earliest));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::arch;
+use crate::mutex::Mutex;
+
+/// Maximum number of pending work items per priority level.
+pub(crate) const QUEUE_LEN: usize = 32;
+
+/// The number of distinct priority levels.
+const NUM_PRIORITIES: usize = 3;
+
+/// Priority of a deferred work item. Higher priority work is always run
+/// before lower priority work.
+#[repr(usize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Priority {
+    High = 0,
+    Normal = 1,
+    Low = 2,
+}
+
+/// A unit of deferred work: a function and the argument to call it with.
+#[derive(Copy, Clone)]
+pub struct Work {
+    func: fn(usize),
+    arg: usize,
+}
+
+impl Work {
+    /// Returns a new work item that calls `func(arg)` when run.
+    pub const fn new(func: fn(usize), arg: usize) -> Work {
+        Work { func, arg }
+    }
+
+    /// Runs this work item.
+    pub(crate) fn run(self) {
+        (self.func)(self.arg)
+    }
+}
+
+/// Counters for a single priority level.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Stats {
+    /// Number of work items successfully enqueued.
+    pub enqueued: u64,
+    /// Number of work items that have been run.
+    pub executed: u64,
+    /// Number of work items rejected because the queue was full.
+    pub dropped: u64,
+    /// Largest number of items ever pending at once.
+    pub high_water: usize,
+}
+
+/// A fixed-capacity FIFO of work items for a single priority level.
+struct Ring {
+    items: [Option<Work>; QUEUE_LEN],
+    head: usize,
+    len: usize,
+    stats: Stats,
+}
+
+impl Ring {
+    const fn new() -> Ring {
+        Ring {
+            items: [None; QUEUE_LEN],
+            head: 0,
+            len: 0,
+            stats: Stats { enqueued: 0, executed: 0, dropped: 0, high_water: 0 },
+        }
+    }
+
+    fn push(&mut self, work: Work) -> Result<(), Work> {
+        if self.len == QUEUE_LEN {
+            self.stats.dropped += 1;
+            return Err(work);
+        }
+
+        self.items[(self.head + self.len) % QUEUE_LEN] = Some(work);
+        self.len += 1;
+        self.stats.enqueued += 1;
+        if self.len > self.stats.high_water {
+            self.stats.high_water = self.len;
+        }
+
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<Work> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let work = self.items[self.head].take();
+        self.head = (self.head + 1) % QUEUE_LEN;
+        self.len -= 1;
+        work
+    }
+}
+
+/// A queue of work deferred from interrupt context ("bottom halves").
+///
+/// Interrupt handlers should do the minimum amount of work required to
+/// acknowledge the interrupt and `enqueue` the rest. Pending work is run later
+/// in thread context by calling `run_pending()`.
+pub struct WorkQueue {
+    rings: [Ring; NUM_PRIORITIES],
+}
+
+impl WorkQueue {
+    /// Returns a new, empty `WorkQueue`.
+    pub const fn new() -> WorkQueue {
+        WorkQueue { rings: [Ring::new(), Ring::new(), Ring::new()] }
+    }
+
+    /// Enqueues `work` at priority `priority`.
+    ///
+    /// # Errors
+    ///
+    /// If the queue for `priority` is full, the work item is returned as an
+    /// `Err` and counted as dropped.
+    pub fn enqueue(&mut self, priority: Priority, work: Work) -> Result<(), Work> {
+        self.rings[priority as usize].push(work)
+    }
+
+    /// Removes and returns the highest priority pending work item, if any.
+    pub(crate) fn next(&mut self) -> Option<Work> {
+        for ring in self.rings.iter_mut() {
+            if let Some(work) = ring.pop() {
+                ring.stats.executed += 1;
+                return Some(work);
+            }
+        }
+
+        None
+    }
+
+    /// Returns the number of pending work items across all priorities.
+    pub fn pending(&self) -> usize {
+        self.rings.iter().map(|ring| ring.len).sum()
+    }
+
+    /// Returns the statistics for priority level `priority`.
+    pub fn stats(&self, priority: Priority) -> Stats {
+        self.rings[priority as usize].stats
+    }
+}
+
+/// Global deferred work queue. Its lock is only ever taken with IRQs masked,
+/// so an IRQ handler calling `defer()` can never find it held by the code it
+/// interrupted. It is private so that every lock goes through `defer()`,
+/// `run_pending()` and `stats()`.
+static WORKQUEUE: Mutex<WorkQueue> = Mutex::new(WorkQueue::new());
+
+/// Defers `func(arg)` to be run later in thread context at `priority`.
+///
+/// # Errors
+///
+/// Returns `Err(())` if the queue for `priority` is full.
+pub fn defer(priority: Priority, func: fn(usize), arg: usize) -> Result<(), ()> {
+    arch::without_interrupts(|| {
+        WORKQUEUE.lock().enqueue(priority, Work::new(func, arg)).map_err(|_| ())
+    })
+}
+
+/// Returns the statistics of the global queue for priority level `priority`.
+pub fn stats(priority: Priority) -> Stats {
+    arch::without_interrupts(|| WORKQUEUE.lock().stats(priority))
+}
+
+/// Runs all pending work items, highest priority first, until the queue is
+/// empty. Returns the number of work items that were run.
+///
+/// The queue lock is not held while a work item runs, so work items may
+/// themselves defer more work.
+pub fn run_pending() -> usize {
+    let mut ran = 0;
+    loop {
+        let work = match arch::without_interrupts(|| WORKQUEUE.lock().next()) {
+            Some(work) => work,
+            None => return ran,
+        };
+
+        work.run();
+        ran += 1;
+    }
+}
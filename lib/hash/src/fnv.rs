@@ -0,0 +1,90 @@
+use core::hash::{BuildHasher, Hasher};
+
+const FNV64_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV64_PRIME: u64 = 0x100000001b3;
+
+const FNV32_OFFSET_BASIS: u32 = 0x811c9dc5;
+const FNV32_PRIME: u32 = 0x01000193;
+
+/// A 64-bit FNV-1a hasher.
+///
+/// FNV-1a is very fast for short keys such as names and small integers, but
+/// offers no protection against maliciously chosen keys. Use
+/// [`SipHasher13`](crate::SipHasher13) with a secret key where that matters.
+#[derive(Debug, Copy, Clone)]
+pub struct FnvHasher(u64);
+
+impl FnvHasher {
+    /// Returns a new `FnvHasher` in its initial state.
+    pub const fn new() -> FnvHasher {
+        FnvHasher(FNV64_OFFSET_BASIS)
+    }
+}
+
+impl Default for FnvHasher {
+    fn default() -> FnvHasher {
+        FnvHasher::new()
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(FNV64_PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// A 32-bit FNV-1a hasher.
+///
+/// `finish()` returns the 32-bit hash zero-extended to 64 bits. The 32-bit
+/// value is also available from `finish32()`.
+#[derive(Debug, Copy, Clone)]
+pub struct Fnv32Hasher(u32);
+
+impl Fnv32Hasher {
+    /// Returns a new `Fnv32Hasher` in its initial state.
+    pub const fn new() -> Fnv32Hasher {
+        Fnv32Hasher(FNV32_OFFSET_BASIS)
+    }
+
+    /// Returns the 32-bit hash of the values written so far.
+    pub fn finish32(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Default for Fnv32Hasher {
+    fn default() -> Fnv32Hasher {
+        Fnv32Hasher::new()
+    }
+}
+
+impl Hasher for Fnv32Hasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u32).wrapping_mul(FNV32_PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0 as u64
+    }
+}
+
+/// A `BuildHasher` producing [`FnvHasher`]s, for use as the `S` parameter of
+/// hash maps and sets.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct FnvBuildHasher;
+
+impl BuildHasher for FnvBuildHasher {
+    type Hasher = FnvHasher;
+
+    fn build_hasher(&self) -> FnvHasher {
+        FnvHasher::new()
+    }
+}
@@ -0,0 +1,16 @@
+#![no_std]
+
+//! Small, deterministic hash functions for kernel use.
+//!
+//! Both hashers produce the same output for the same input on every boot and
+//! every machine (SipHash for a given key), which keeps hash table iteration
+//! order reproducible while debugging.
+
+#[cfg(test)]
+mod tests;
+
+mod fnv;
+mod sip;
+
+pub use fnv::{Fnv32Hasher, FnvBuildHasher, FnvHasher};
+pub use sip::{SipBuildHasher, SipHasher13};
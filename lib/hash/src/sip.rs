@@ -0,0 +1,141 @@
+use core::hash::{BuildHasher, Hasher};
+
+/// SipHash-1-3: SipHash with one compression round and three finalization
+/// rounds, keyed by a 128-bit key `(k0, k1)`.
+///
+/// This is the variant used by the Rust standard library. With a secret,
+/// random key it resists hash flooding; with a fixed key it is simply a
+/// good-quality deterministic hash.
+#[derive(Debug, Copy, Clone)]
+pub struct SipHasher13 {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+    /// Bytes not yet compressed, little-endian, low `ntail` bytes valid.
+    tail: u64,
+    ntail: usize,
+    /// Total number of bytes written.
+    length: usize,
+}
+
+impl SipHasher13 {
+    /// Returns a new `SipHasher13` keyed with `(k0, k1)`.
+    pub const fn new_with_keys(k0: u64, k1: u64) -> SipHasher13 {
+        SipHasher13 {
+            v0: k0 ^ 0x736f6d6570736575,
+            v1: k1 ^ 0x646f72616e646f6d,
+            v2: k0 ^ 0x6c7967656e657261,
+            v3: k1 ^ 0x7465646279746573,
+            tail: 0,
+            ntail: 0,
+            length: 0,
+        }
+    }
+
+    /// Returns a new `SipHasher13` keyed with `(0, 0)`.
+    pub const fn new() -> SipHasher13 {
+        SipHasher13::new_with_keys(0, 0)
+    }
+
+    #[inline(always)]
+    fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13);
+        self.v1 ^= self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16);
+        self.v3 ^= self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21);
+        self.v3 ^= self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17);
+        self.v1 ^= self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    /// Compresses the 8-byte little-endian word `m`.
+    #[inline(always)]
+    fn compress(&mut self, m: u64) {
+        self.v3 ^= m;
+        self.round();
+        self.v0 ^= m;
+    }
+}
+
+impl Default for SipHasher13 {
+    fn default() -> SipHasher13 {
+        SipHasher13::new()
+    }
+}
+
+impl Hasher for SipHasher13 {
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.length = self.length.wrapping_add(bytes.len());
+
+        // Top up a partially filled tail first.
+        while self.ntail != 0 && !bytes.is_empty() {
+            self.tail |= (bytes[0] as u64) << (8 * self.ntail);
+            self.ntail = (self.ntail + 1) % 8;
+            bytes = &bytes[1..];
+            if self.ntail == 0 {
+                let m = self.tail;
+                self.tail = 0;
+                self.compress(m);
+            }
+        }
+
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(chunk);
+            self.compress(u64::from_le_bytes(word));
+        }
+
+        for (i, &byte) in chunks.remainder().iter().enumerate() {
+            self.tail |= (byte as u64) << (8 * i);
+        }
+        self.ntail = chunks.remainder().len();
+    }
+
+    fn finish(&self) -> u64 {
+        let mut state = *self;
+        let b = ((self.length as u64 & 0xff) << 56) | self.tail;
+
+        state.compress(b);
+        state.v2 ^= 0xff;
+        state.round();
+        state.round();
+        state.round();
+
+        state.v0 ^ state.v1 ^ state.v2 ^ state.v3
+    }
+}
+
+/// A `BuildHasher` producing [`SipHasher13`]s with a fixed key, for use as the
+/// `S` parameter of hash maps and sets.
+///
+/// `Default` uses the key `(0, 0)`. Use `new()` with a random key where
+/// resistance to chosen keys matters.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SipBuildHasher {
+    k0: u64,
+    k1: u64,
+}
+
+impl SipBuildHasher {
+    /// Returns a new `SipBuildHasher` whose hashers are keyed with `(k0, k1)`.
+    pub const fn new(k0: u64, k1: u64) -> SipBuildHasher {
+        SipBuildHasher { k0, k1 }
+    }
+}
+
+impl BuildHasher for SipBuildHasher {
+    type Hasher = SipHasher13;
+
+    fn build_hasher(&self) -> SipHasher13 {
+        SipHasher13::new_with_keys(self.k0, self.k1)
+    }
+}
@@ -0,0 +1,109 @@
+extern crate std;
+
+use core::hash::{BuildHasher, Hash, Hasher};
+
+use crate::*;
+
+fn fnv64(bytes: &[u8]) -> u64 {
+    let mut hasher = FnvHasher::new();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+fn fnv32(bytes: &[u8]) -> u32 {
+    let mut hasher = Fnv32Hasher::new();
+    hasher.write(bytes);
+    hasher.finish32()
+}
+
+#[test]
+fn fnv_reference_values() {
+    assert_eq!(fnv64(b""), 0xcbf29ce484222325);
+    assert_eq!(fnv64(b"a"), 0xaf63dc4c8601ec8c);
+    assert_eq!(fnv64(b"foobar"), 0x85944171f73967e8);
+
+    assert_eq!(fnv32(b""), 0x811c9dc5);
+    assert_eq!(fnv32(b"a"), 0xe40c292c);
+    assert_eq!(fnv32(b"foobar"), 0xbf9cf968);
+}
+
+#[test]
+fn fnv_is_streaming() {
+    let mut hasher = FnvHasher::new();
+    hasher.write(b"foo");
+    hasher.write(b"bar");
+    assert_eq!(hasher.finish(), fnv64(b"foobar"));
+}
+
+/// SipHash-1-3 of the messages `[]`, `[0]`, `[0, 1]`, ... under the key
+/// `0x00..=0x0f`, from the SipHash-1-3 reference test vectors.
+const SIP13_VECTORS: [[u8; 8]; 9] = [
+    [0xdc, 0xc4, 0x0f, 0x05, 0x58, 0x01, 0xac, 0xab],
+    [0x93, 0xca, 0x57, 0x7d, 0xf3, 0x9b, 0xf4, 0xc9],
+    [0x4d, 0xd4, 0xc7, 0x4d, 0x02, 0x9b, 0xcb, 0x82],
+    [0xfb, 0xf7, 0xdd, 0xe7, 0xb8, 0x0a, 0xf8, 0x8b],
+    [0x28, 0x83, 0xd3, 0x88, 0x60, 0x57, 0x75, 0xcf],
+    [0x67, 0x3b, 0x53, 0x49, 0x2f, 0xd5, 0xf9, 0xde],
+    [0xa7, 0x22, 0x9f, 0xc5, 0x50, 0x2b, 0x0d, 0xc5],
+    [0x40, 0x11, 0xb1, 0x9b, 0x98, 0x7d, 0x92, 0xd3],
+    [0x8e, 0x9a, 0x29, 0x8d, 0x11, 0x95, 0x90, 0x36],
+];
+
+#[test]
+fn sip_reference_vectors() {
+    let k0 = u64::from_le_bytes([0, 1, 2, 3, 4, 5, 6, 7]);
+    let k1 = u64::from_le_bytes([8, 9, 10, 11, 12, 13, 14, 15]);
+    let data: std::vec::Vec<u8> = (0..SIP13_VECTORS.len() as u8).collect();
+    for (len, expected) in SIP13_VECTORS.iter().enumerate() {
+        let mut hasher = SipHasher13::new_with_keys(k0, k1);
+        hasher.write(&data[..len]);
+        assert_eq!(hasher.finish(), u64::from_le_bytes(*expected), "length {}", len);
+    }
+}
+
+#[test]
+fn sip_is_streaming() {
+    let data: std::vec::Vec<u8> = (0..64u8).collect();
+    let mut whole = SipHasher13::new_with_keys(1, 2);
+    whole.write(&data);
+
+    for split in 0..data.len() {
+        let mut parts = SipHasher13::new_with_keys(1, 2);
+        parts.write(&data[..split]);
+        parts.write(&data[split..]);
+        assert_eq!(parts.finish(), whole.finish(), "split at {}", split);
+    }
+}
+
+#[test]
+fn sip_keys_matter() {
+    let hash = |k0, k1| {
+        let mut hasher = SipHasher13::new_with_keys(k0, k1);
+        hasher.write(b"key");
+        hasher.finish()
+    };
+
+    assert_eq!(hash(1, 2), hash(1, 2));
+    assert_ne!(hash(1, 2), hash(2, 1));
+}
+
+#[test]
+fn build_hashers() {
+    let mut direct = SipHasher13::new_with_keys(1, 2);
+    "key".hash(&mut direct);
+    let mut built = SipBuildHasher::new(1, 2).build_hasher();
+    "key".hash(&mut built);
+    assert_eq!(built.finish(), direct.finish());
+
+    let mut direct = SipHasher13::new();
+    "key".hash(&mut direct);
+    let mut built = SipBuildHasher::default().build_hasher();
+    "key".hash(&mut built);
+    assert_eq!(built.finish(), direct.finish());
+
+    let mut direct = FnvHasher::new();
+    42u32.hash(&mut direct);
+    let mut built = FnvBuildHasher.build_hasher();
+    42u32.hash(&mut built);
+    assert_eq!(built.finish(), direct.finish());
+}
@@ -1,6 +1,7 @@
 /// The address where I/O peripherals are mapped to.
-pub const IO_BASE: usize   = 0x3F000000;
-pub const CLOCK_HZ: u64 = 250 * 1000 * 1000;
+pub const IO_BASE: usize = shim::target::IO_BASE;
+/// The frequency of the core clock, which clocks the mini UART, in Hz.
+pub const CLOCK_HZ: u64 = shim::target::CORE_CLOCK_HZ;
 
 /// Generates `pub enums` with no variants for each `ident` passed in.
 pub macro states($($name:ident),*) {
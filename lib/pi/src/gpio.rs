@@ -6,6 +6,7 @@ use volatile::{Volatile, WriteVolatile, ReadVolatile, Reserved};
 
 /// An alternative GPIO function.
 #[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Function {
     Input = 0b000,
     Output = 0b001,
@@ -17,6 +18,23 @@ pub enum Function {
     Alt5 = 0b010
 }
 
+/// A condition on an input pin that sets its event detect status.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Detect {
+    /// A synchronous (debounced) low to high transition.
+    RisingEdge,
+    /// A synchronous (debounced) high to low transition.
+    FallingEdge,
+    /// An asynchronous, undebounced low to high transition.
+    AsyncRisingEdge,
+    /// An asynchronous, undebounced high to low transition.
+    AsyncFallingEdge,
+    /// The pin being high.
+    HighLevel,
+    /// The pin being low.
+    LowLevel,
+}
+
 #[repr(C)]
 #[allow(non_snake_case)]
 struct Registers {
@@ -106,6 +124,40 @@ impl Gpio<Uninitialized> {
         unimplemented!()
     }
 
+    /// Configures the function of every pin in `pins` at once.
+    ///
+    /// Pins whose function select bits share an `FSEL` register are updated
+    /// with a single read-modify-write of that register, so a peripheral pin
+    /// group (e.g. UART TX/RX, or the EMMC pins) switches over without passing
+    /// through intermediate configurations. If a pin appears more than once,
+    /// the last entry wins.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any pin > `53`. No registers are written in that case.
+    pub fn configure_group(pins: &[(u8, Function)]) {
+        // Per `FSEL` register: (bits to clear, bits to set).
+        let mut masks = [(0u32, 0u32); 6];
+        for &(pin, function) in pins {
+            if pin > 53 {
+                panic!("Gpio::configure_group(): pin {} exceeds maximum of 53", pin);
+            }
+
+            let (index, shift) = ((pin / 10) as usize, (pin % 10) * 3);
+            let (ref mut clear, ref mut set) = masks[index];
+            *clear |= 0b111 << shift;
+            *set = (*set & !(0b111 << shift)) | ((function as u32) << shift);
+        }
+
+        let registers = unsafe { &mut *(GPIO_BASE as *mut Registers) };
+        for (fsel, &(clear, set)) in registers.FSEL.iter_mut().zip(masks.iter()) {
+            if clear != 0 {
+                let value = fsel.read();
+                fsel.write((value & !clear) | set);
+            }
+        }
+    }
+
     /// Sets this pin to be an _output_ pin. Consumes self and returns a `Gpio`
     /// structure in the `Output` state.
     pub fn into_output(self) -> Gpio<Output> {
@@ -137,4 +189,64 @@ impl Gpio<Input> {
     pub fn level(&mut self) -> bool {
         unimplemented!()
     }
+
+    /// Returns the register bank index and bit mask of this pin in the
+    /// two-register pin banks.
+    fn bank(&self) -> (usize, u32) {
+        ((self.pin / 32) as usize, 1 << (self.pin % 32))
+    }
+
+    /// Returns the enable registers for `detect`.
+    fn detect_registers(&mut self, detect: Detect) -> &mut [Volatile<u32>; 2] {
+        match detect {
+            Detect::RisingEdge => &mut self.registers.REN,
+            Detect::FallingEdge => &mut self.registers.FEN,
+            Detect::AsyncRisingEdge => &mut self.registers.AREN,
+            Detect::AsyncFallingEdge => &mut self.registers.AFEN,
+            Detect::HighLevel => &mut self.registers.HEN,
+            Detect::LowLevel => &mut self.registers.LEN,
+        }
+    }
+
+    /// Enables setting this pin's event detect status on `detect`. Any
+    /// number of conditions may be enabled at once.
+    pub fn enable_detect(&mut self, detect: Detect) {
+        let (index, mask) = self.bank();
+        let register = &mut self.detect_registers(detect)[index];
+        let value = register.read();
+        register.write(value | mask);
+    }
+
+    /// Disables setting this pin's event detect status on `detect`.
+    pub fn disable_detect(&mut self, detect: Detect) {
+        let (index, mask) = self.bank();
+        let register = &mut self.detect_registers(detect)[index];
+        let value = register.read();
+        register.write(value & !mask);
+    }
+
+    /// Enables detection of rising edges on this pin.
+    pub fn enable_rising_edge(&mut self) {
+        self.enable_detect(Detect::RisingEdge)
+    }
+
+    /// Enables detection of falling edges on this pin.
+    pub fn enable_falling_edge(&mut self) {
+        self.enable_detect(Detect::FallingEdge)
+    }
+
+    /// Returns `true` if an enabled condition has been detected on this pin
+    /// since the status was last cleared with `clear_event()`.
+    pub fn event_detected(&mut self) -> bool {
+        let (index, mask) = self.bank();
+        self.registers.EDS[index].read() & mask != 0
+    }
+
+    /// Clears this pin's event detect status. If a level condition is enabled
+    /// and still holds, the status is set again immediately.
+    pub fn clear_event(&mut self) {
+        let (index, mask) = self.bank();
+        // Bits of `GPEDS` are cleared by writing a 1 to them.
+        self.registers.EDS[index].write(mask);
+    }
 }
@@ -1,4 +1,6 @@
 use crate::common::IO_BASE;
+#[cfg(target_arch = "aarch64")]
+use core::sync::atomic::{AtomicU64, Ordering};
 use core::time::Duration;
 
 use volatile::prelude::*;
@@ -16,6 +18,10 @@ struct Registers {
     COMPARE: [Volatile<u32>; 4]
 }
 
+/// The compare channel used by the kernel. Channels 0 and 2 are used by the
+/// GPU.
+const COMPARE_CHANNEL: usize = 1;
+
 /// The Raspberry Pi ARM system timer.
 pub struct Timer {
     registers: &'static mut Registers
@@ -32,17 +38,150 @@ impl Timer {
     /// Reads the system timer's counter and returns Duration.
     /// `CLO` and `CHI` together can represent the number of elapsed microseconds.
     pub fn read(&self) -> Duration {
-        unimplemented!()
+        // `CLO` may wrap between the two reads; if `CHI` changed, read `CLO`
+        // again for the new high word.
+        let mut hi = self.registers.CHI.read();
+        let mut lo = self.registers.CLO.read();
+        let hi_again = self.registers.CHI.read();
+        if hi != hi_again {
+            hi = hi_again;
+            lo = self.registers.CLO.read();
+        }
+
+        Duration::from_micros(((hi as u64) << 32) | lo as u64)
+    }
+
+    /// Sets the timer to match, raising the system timer interrupt, once the
+    /// counter reaches `deadline`. Any pending match is cleared. Only the low
+    /// 32 bits of the counter are compared, so `deadline` must be less than
+    /// about 71 minutes away.
+    pub fn set_compare(&mut self, deadline: Duration) {
+        self.clear_match();
+        self.registers.COMPARE[COMPARE_CHANNEL].write(deadline.as_micros() as u32);
+    }
+
+    /// Clears a pending match on the kernel's compare channel, acknowledging
+    /// the system timer interrupt it raised.
+    pub fn clear_match(&mut self) {
+        self.registers.CS.write(1 << COMPARE_CHANNEL);
     }
 }
 
 /// Returns current time.
 pub fn current_time() -> Duration {
-    unimplemented!()
+    Timer::new().read()
 }
 
 /// Spins until `t` duration have passed.
 pub fn spin_sleep(t: Duration) {
-    unimplemented!()
+    let start = current_time();
+    while current_time() - start < t {}
+}
+
+// The calibrated delay loop below is AArch64 assembly, so it only exists when
+// building for the Pi, not in host builds of this crate.
+
+/// Number of iterations of the `spin_sleep_cycles` delay loop used to
+/// calibrate it against the system timer.
+#[cfg(target_arch = "aarch64")]
+const CALIBRATION_LOOPS: u64 = 1_000_000;
+
+/// Number of minimal `spin_sleep_us` calls timed to measure the fixed cost of
+/// a call.
+#[cfg(target_arch = "aarch64")]
+const OVERHEAD_CALLS: u64 = 100_000;
+
+/// Delays at or above this many microseconds poll the system timer instead of
+/// running the calibrated delay loop.
+#[cfg(target_arch = "aarch64")]
+const POLL_THRESHOLD_US: u64 = 100;
+
+/// Calibrated number of `spin_sleep_cycles` iterations per microsecond, scaled
+/// by 1024 to keep sub-microsecond precision. Zero if `calibrate()` has not
+/// been called.
+#[cfg(target_arch = "aarch64")]
+static LOOPS_PER_US_X1024: AtomicU64 = AtomicU64::new(0);
+
+/// Calibrated fixed cost of a `spin_sleep_us` call outside of the delay loop
+/// (the call itself, the atomic loads, and the arithmetic), in delay-loop
+/// iterations scaled by 1024.
+#[cfg(target_arch = "aarch64")]
+static OVERHEAD_LOOPS_X1024: AtomicU64 = AtomicU64::new(0);
+
+/// Executes `cycles` iterations of a minimal delay loop (a decrement and a
+/// branch). This never touches memory or peripherals, so it is safe to use
+/// in timing-sensitive bit-banging where even a timer read is too slow.
+#[cfg(target_arch = "aarch64")]
+#[inline(always)]
+pub fn spin_sleep_cycles(cycles: u64) {
+    let mut remaining = cycles;
+    if remaining == 0 {
+        return;
+    }
+
+    unsafe {
+        asm!("1: subs $0, $0, #1
+                 b.ne 1b"
+             : "+r"(remaining)
+             :
+             : "cc"
+             : "volatile");
+    }
+}
+
+/// Waits for the system timer to tick and returns the new time, so that a
+/// measurement starting there isn't off by up to a full microsecond.
+#[cfg(target_arch = "aarch64")]
+fn next_tick() -> Duration {
+    let tick = current_time();
+    let mut now = current_time();
+    while now == tick {
+        now = current_time();
+    }
+
+    now
 }
 
+/// Calibrates `spin_sleep_cycles` against the system timer and returns the
+/// measured number of delay-loop iterations per microsecond. Also measures
+/// the fixed cost of a `spin_sleep_us` call, which later calls subtract from
+/// their delay. Must be called again if the CPU frequency changes.
+#[cfg(target_arch = "aarch64")]
+pub fn calibrate() -> u64 {
+    let start = next_tick();
+    spin_sleep_cycles(CALIBRATION_LOOPS);
+    let elapsed_us = (current_time() - start).as_micros() as u64;
+
+    let loops_x1024 = (CALIBRATION_LOOPS * 1024) / elapsed_us.max(1);
+    LOOPS_PER_US_X1024.store(loops_x1024, Ordering::Relaxed);
+    OVERHEAD_LOOPS_X1024.store(0, Ordering::Relaxed);
+
+    // A zero-length delay runs everything in `spin_sleep_us` but the delay
+    // loop itself, so timing many of them measures the fixed cost of a call.
+    let start = next_tick();
+    for _ in 0..OVERHEAD_CALLS {
+        spin_sleep_us(0);
+    }
+    let elapsed_us = (current_time() - start).as_micros() as u64;
+
+    OVERHEAD_LOOPS_X1024.store((elapsed_us * loops_x1024) / OVERHEAD_CALLS, Ordering::Relaxed);
+    loops_x1024 / 1024
+}
+
+/// Spins until `us` microseconds have passed.
+///
+/// Short delays use the delay loop calibrated by `calibrate()`, which is
+/// accurate well below a microsecond: the fixed cost of the call is taken off
+/// the delay, so delays shorter than that cost return as soon as possible.
+/// Longer delays, or any delay before `calibrate()` has been called, poll the
+/// system timer.
+#[cfg(target_arch = "aarch64")]
+pub fn spin_sleep_us(us: u64) {
+    let loops_x1024 = LOOPS_PER_US_X1024.load(Ordering::Relaxed);
+    if us >= POLL_THRESHOLD_US || loops_x1024 == 0 {
+        spin_sleep(Duration::from_micros(us));
+    } else {
+        let overhead_x1024 = OVERHEAD_LOOPS_X1024.load(Ordering::Relaxed);
+        spin_sleep_cycles((us * loops_x1024).saturating_sub(overhead_x1024) / 1024);
+    }
+}
@@ -17,6 +17,12 @@ const MU_REG_BASE: usize = IO_BASE + 0x215040;
 /// The `AUXENB` register from page 9 of the BCM2837 documentation.
 const AUX_ENABLES: *mut Volatile<u8> = (IO_BASE + 0x215004) as *mut Volatile<u8>;
 
+/// The `AUX_MU_IO_REG` register, accessed directly by `emergency_write()`.
+const AUX_MU_IO: *mut Volatile<u8> = MU_REG_BASE as *mut Volatile<u8>;
+
+/// The `AUX_MU_LSR_REG` register, accessed directly by `emergency_write()`.
+const AUX_MU_LSR: *const ReadVolatile<u8> = (MU_REG_BASE + 0x14) as *const ReadVolatile<u8>;
+
 /// Enum representing bit fields of the `AUX_MU_LSR_REG` register.
 #[repr(u8)]
 enum LsrStatus {
@@ -91,6 +97,58 @@ impl MiniUart {
     }
 }
 
+/// The number of times `emergency_write()` polls for room in the transmit
+/// FIFO before giving up. At 115200 baud a byte leaves the FIFO every ~87us,
+/// far sooner than this many polls.
+const EMERGENCY_SPINS: usize = 1_000_000;
+
+/// Writes `bytes` to the mini UART by polling its registers directly, without
+/// going through a `MiniUart`. Like the `fmt::Write` implementation, a `\r` is
+/// written before every `\n`.
+///
+/// This takes no locks and does not format, so it is safe to call from
+/// exception handlers and other contexts that may have interrupted a holder of
+/// the `MiniUart`. Output may interleave with that of an interrupted writer.
+///
+/// Nothing is written if the mini UART is not enabled. If the transmit FIFO
+/// stays full for `EMERGENCY_SPINS` polls, the remaining bytes are dropped, so
+/// this always returns.
+pub fn emergency_write(bytes: &[u8]) {
+    unsafe {
+        if (*AUX_ENABLES).read() & 1 == 0 {
+            return;
+        }
+    }
+
+    for &byte in bytes {
+        if byte == b'\n' && !emergency_write_byte(b'\r') {
+            return;
+        }
+
+        if !emergency_write_byte(byte) {
+            return;
+        }
+    }
+}
+
+/// Writes `byte` to the mini UART once the transmit FIFO has room. Returns
+/// `false` without writing if it stays full for `EMERGENCY_SPINS` polls.
+fn emergency_write_byte(byte: u8) -> bool {
+    unsafe {
+        let mut spins = 0;
+        while (*AUX_MU_LSR).read() & (LsrStatus::TxAvailable as u8) == 0 {
+            spins += 1;
+            if spins == EMERGENCY_SPINS {
+                return false;
+            }
+        }
+
+        (*AUX_MU_IO).write(byte);
+    }
+
+    true
+}
+
 // FIXME: Implement `fmt::Write` for `MiniUart`. A b'\r' byte should be written
 // before writing any b'\n' byte.
 
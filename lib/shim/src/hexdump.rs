@@ -0,0 +1,55 @@
+use core::fmt;
+
+/// Number of bytes shown per line.
+const LINE_LEN: usize = 16;
+
+/// Formats a byte buffer in the classic `hexdump -C` layout: an offset, the
+/// bytes in hex in two groups of eight, and the bytes as ASCII, with
+/// non-printable bytes shown as `.`.
+///
+/// ```text
+/// 00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a        |Hello, world!.|
+/// ```
+///
+/// Lines are separated, not terminated, by `\n`. An empty buffer formats as
+/// nothing. `Debug` and `Display` produce the same output.
+#[derive(Copy, Clone)]
+pub struct HexDump<'a>(pub &'a [u8]);
+
+impl<'a> fmt::Display for HexDump<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, line) in self.0.chunks(LINE_LEN).enumerate() {
+            if i != 0 {
+                f.write_str("\n")?;
+            }
+
+            write!(f, "{:08x} ", i * LINE_LEN)?;
+            for j in 0..LINE_LEN {
+                if j % 8 == 0 {
+                    f.write_str(" ")?;
+                }
+
+                match line.get(j) {
+                    Some(byte) => write!(f, "{:02x} ", byte)?,
+                    None => f.write_str("   ")?,
+                }
+            }
+
+            f.write_str(" |")?;
+            for &byte in line {
+                let c = if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' };
+                write!(f, "{}", c)?;
+            }
+
+            f.write_str("|")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Debug for HexDump<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
@@ -19,5 +19,9 @@ cfg_if::cfg_if! {
 #[macro_use]
 pub mod macros;
 
+pub mod hexdump;
+pub mod sync;
+pub mod target;
+
 #[cfg(test)]
 mod tests;
@@ -32,16 +32,112 @@ macro_rules! const_assert_size {
     }
 }
 
+/// Returns the byte offset of `$field` in `$struct`, e.g.
+/// `field_offset!(Registers, LSR)`. Usable in constants.
+///
+/// The offset is the distance between raw pointers into an uninitialized
+/// `$struct`. No reference to the uninitialized memory is created and nothing
+/// is read from it.
+#[macro_export]
+macro_rules! field_offset {
+    ($struct:ty, $field:ident) => {{
+        let uninit = core::mem::MaybeUninit::<$struct>::uninit();
+        let base = uninit.as_ptr();
+        #[allow(unused_unsafe)]
+        let field = unsafe { core::ptr::addr_of!((*base).$field) };
+        #[allow(unused_unsafe)]
+        let offset = unsafe { (field as *const u8).offset_from(base as *const u8) };
+        offset as usize
+    }};
+}
+
+/// Asserts at compile time that `$field` of `$struct` is at byte offset
+/// `$offset`, e.g. `const_assert_offset!(Registers, LSR, 0x14)`.
+#[macro_export]
+macro_rules! const_assert_offset {
+    ($struct:ty, $field:ident, $offset:expr) => {
+        const _: () = {
+            const OFFSET: usize = $crate::field_offset!($struct, $field);
+            $crate::const_assert!(OFFSET == ($offset));
+        };
+    }
+}
+
+/// Asserts that `$field` of `$struct` is at byte offset `$offset`, e.g.
+/// `assert_offset!(Registers, LSR, 0x14)`. This is checked at run time; use
+/// `const_assert_offset!` to check at compile time.
+#[macro_export]
+macro_rules! assert_offset {
+    ($struct:ty, $field:ident, $offset:expr) => {
+        assert_eq!($crate::field_offset!($struct, $field), ($offset),
+            concat!("offset of ", stringify!($struct), "::", stringify!($field)));
+    }
+}
+
+/// Asserts at compile time that the alignment of `$struct` is `$align`.
+#[macro_export]
+macro_rules! const_assert_align {
+    ($struct:ty, $align:expr) => {
+        $crate::const_assert_eq!(core::mem::align_of::<$struct>(), ($align));
+    }
+}
+
+/// Constructs an `io::Error` of kind `io::ErrorKind::$kind`, e.g.
+/// `newioerr!(InvalidData, "bad sector {}", sector)`. Formatting the message
+/// needs an allocator, so under `no_std` it needs the `alloc` feature; without
+/// it, the format string is the message and the arguments are evaluated but
+/// not formatted.
+#[cfg(any(feature = "alloc", not(feature = "no_std")))]
 #[macro_export]
 macro_rules! newioerr {
     ($kind:tt, $msg:tt) => {
-        io::Error::new(io::ErrorKind::$kind, $msg);
-    }
+        io::Error::new(io::ErrorKind::$kind, $msg)
+    };
+    ($kind:tt, $fmt:literal, $($arg:tt)+) => {
+        io::Error::new(io::ErrorKind::$kind, $crate::macros::format(format_args!($fmt, $($arg)+)))
+    };
+}
+
+/// Constructs an `io::Error` of kind `io::ErrorKind::$kind`, e.g.
+/// `newioerr!(InvalidData, "bad sector {}", sector)`. Formatting the message
+/// needs an allocator, so under `no_std` it needs the `alloc` feature; without
+/// it, the format string is the message and the arguments are evaluated but
+/// not formatted.
+#[cfg(all(feature = "no_std", not(feature = "alloc")))]
+#[macro_export]
+macro_rules! newioerr {
+    ($kind:tt, $msg:tt) => {
+        io::Error::new(io::ErrorKind::$kind, $msg)
+    };
+    ($kind:tt, $fmt:literal, $($arg:tt)+) => {{
+        // Still evaluate the arguments, and check them against `$fmt`, as the
+        // formatting version does.
+        let _ = format_args!($fmt, $($arg)+);
+        io::Error::new(io::ErrorKind::$kind, $fmt)
+    }};
 }
 
+/// Returns `Err` of `newioerr!($kind, ...)`.
 #[macro_export]
 macro_rules! ioerr {
     ($kind:tt, $msg:tt) => {
-        Err(io::Error::new(io::ErrorKind::$kind, $msg));
-    }
-}
\ No newline at end of file
+        Err(io::Error::new(io::ErrorKind::$kind, $msg))
+    };
+    ($kind:tt, $fmt:literal, $($arg:tt)+) => {
+        Err($crate::newioerr!($kind, $fmt, $($arg)+))
+    };
+}
+
+/// Formats `args` for `newioerr!`, so callers need not depend on `alloc`.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub fn format(args: core::fmt::Arguments) -> alloc::string::String {
+    alloc::fmt::format(args)
+}
+
+/// Formats `args` for `newioerr!`.
+#[cfg(all(not(feature = "alloc"), not(feature = "no_std")))]
+#[doc(hidden)]
+pub fn format(args: std::fmt::Arguments) -> String {
+    format!("{}", args)
+}
@@ -1,6 +1,6 @@
 pub use core_io as io;
 
-#[cfg(feature = "alloc")]
 pub mod ffi;
 #[cfg(feature = "alloc")]
 pub mod path;
+pub mod time;
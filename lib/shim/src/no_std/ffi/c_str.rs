@@ -0,0 +1,423 @@
+#[cfg(feature = "alloc")]
+use alloc::borrow::{Borrow, Cow, ToOwned};
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::ascii;
+use core::fmt::{self, Write};
+#[cfg(feature = "alloc")]
+use core::ops;
+use core::slice;
+use core::str::{self, Utf8Error};
+
+use super::c_char;
+
+/// Representation of a borrowed C string: a slice of bytes with no interior
+/// nul bytes, terminated by a nul byte.
+///
+/// This mirrors `std::ffi::CStr`. A `&CStr` can be created from a raw pointer
+/// to a nul-terminated string with [`from_ptr`], or checked out of a byte
+/// slice with [`from_bytes_with_nul`].
+///
+/// [`from_ptr`]: #method.from_ptr
+/// [`from_bytes_with_nul`]: #method.from_bytes_with_nul
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct CStr {
+    // Includes the nul terminator.
+    inner: [u8],
+}
+
+/// An error indicating that a byte slice is not a valid C string: it either
+/// contains an interior nul byte or is not nul-terminated.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FromBytesWithNulError {
+    kind: FromBytesWithNulErrorKind,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum FromBytesWithNulErrorKind {
+    InteriorNul(usize),
+    NotNulTerminated,
+}
+
+impl fmt::Display for FromBytesWithNulError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            FromBytesWithNulErrorKind::InteriorNul(pos) => {
+                write!(f, "data provided contains an interior nul byte at byte pos {}", pos)
+            }
+            FromBytesWithNulErrorKind::NotNulTerminated => {
+                f.write_str("data provided is not nul terminated")
+            }
+        }
+    }
+}
+
+impl CStr {
+    /// Wraps a raw C string with a safe C string wrapper.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a nul-terminated string that stays valid and
+    /// unmodified for the lifetime `'a`.
+    pub unsafe fn from_ptr<'a>(ptr: *const c_char) -> &'a CStr {
+        let ptr = ptr as *const u8;
+        let mut len = 0;
+        while *ptr.add(len) != 0 {
+            len += 1;
+        }
+
+        CStr::from_bytes_with_nul_unchecked(slice::from_raw_parts(ptr, len + 1))
+    }
+
+    /// Creates a C string wrapper from a byte slice whose last byte, and only
+    /// that byte, is nul.
+    pub fn from_bytes_with_nul(bytes: &[u8]) -> Result<&CStr, FromBytesWithNulError> {
+        match bytes.iter().position(|&b| b == 0) {
+            Some(pos) if pos + 1 == bytes.len() => {
+                Ok(unsafe { CStr::from_bytes_with_nul_unchecked(bytes) })
+            }
+            Some(pos) => Err(FromBytesWithNulError {
+                kind: FromBytesWithNulErrorKind::InteriorNul(pos),
+            }),
+            None => Err(FromBytesWithNulError {
+                kind: FromBytesWithNulErrorKind::NotNulTerminated,
+            }),
+        }
+    }
+
+    /// Unsafely creates a C string wrapper from a byte slice.
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must end with a nul byte and contain no other nul bytes.
+    pub unsafe fn from_bytes_with_nul_unchecked(bytes: &[u8]) -> &CStr {
+        &*(bytes as *const [u8] as *const CStr)
+    }
+
+    /// Returns a pointer to the start of the nul-terminated string.
+    pub fn as_ptr(&self) -> *const c_char {
+        self.inner.as_ptr() as *const c_char
+    }
+
+    /// Returns the contents of this C string as a byte slice, without the
+    /// trailing nul byte.
+    pub fn to_bytes(&self) -> &[u8] {
+        &self.inner[..self.inner.len() - 1]
+    }
+
+    /// Returns the contents of this C string as a byte slice, including the
+    /// trailing nul byte.
+    pub fn to_bytes_with_nul(&self) -> &[u8] {
+        &self.inner
+    }
+
+    /// Returns the contents of this C string as a `&str` if it is valid UTF-8.
+    pub fn to_str(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(self.to_bytes())
+    }
+
+    /// Returns the contents of this C string as a string, replacing invalid
+    /// UTF-8 sequences with `U+FFFD REPLACEMENT CHARACTER`.
+    #[cfg(feature = "alloc")]
+    pub fn to_string_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(self.to_bytes())
+    }
+}
+
+impl fmt::Debug for CStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        for byte in self.to_bytes().iter().flat_map(|&b| ascii::escape_default(b)) {
+            f.write_char(byte as char)?;
+        }
+
+        f.write_char('"')
+    }
+}
+
+impl Default for &CStr {
+    fn default() -> Self {
+        unsafe { CStr::from_bytes_with_nul_unchecked(b"\0") }
+    }
+}
+
+impl AsRef<CStr> for CStr {
+    fn as_ref(&self) -> &CStr {
+        self
+    }
+}
+
+/// An owned, nul-terminated C string with no interior nul bytes.
+///
+/// This mirrors `std::ffi::CString`. It dereferences to [`CStr`].
+#[cfg(feature = "alloc")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct CString {
+    // Includes the nul terminator.
+    inner: Box<[u8]>,
+}
+
+/// An error indicating that an interior nul byte was found when creating a
+/// `CString`. The bytes that were passed in can be recovered with
+/// [`into_vec`](#method.into_vec).
+#[cfg(feature = "alloc")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NulError(usize, Vec<u8>);
+
+#[cfg(feature = "alloc")]
+impl NulError {
+    /// Returns the position of the nul byte in the bytes passed in.
+    pub fn nul_position(&self) -> usize {
+        self.0
+    }
+
+    /// Consumes this error, returning the bytes that were passed in.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.1
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for NulError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "nul byte found in provided data at position: {}", self.0)
+    }
+}
+
+/// An error indicating invalid UTF-8 when converting a `CString` into a
+/// `String`. The `CString` can be recovered with
+/// [`into_cstring`](#method.into_cstring).
+#[cfg(feature = "alloc")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IntoStringError {
+    inner: CString,
+    error: Utf8Error,
+}
+
+#[cfg(feature = "alloc")]
+impl IntoStringError {
+    /// Consumes this error, returning the original `CString`.
+    pub fn into_cstring(self) -> CString {
+        self.inner
+    }
+
+    /// Returns the UTF-8 error that caused the conversion to fail.
+    pub fn utf8_error(&self) -> Utf8Error {
+        self.error
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for IntoStringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("C string contained non-utf8 bytes")
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl CString {
+    /// Creates a new C string from a container of bytes, appending the nul
+    /// terminator.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `NulError` if the bytes contain a nul byte.
+    pub fn new<T: Into<Vec<u8>>>(t: T) -> Result<CString, NulError> {
+        let bytes = t.into();
+        match bytes.iter().position(|&b| b == 0) {
+            Some(pos) => Err(NulError(pos, bytes)),
+            None => Ok(unsafe { CString::from_vec_unchecked(bytes) }),
+        }
+    }
+
+    /// Creates a C string from a vector of bytes, appending the nul
+    /// terminator without checking for interior nul bytes.
+    ///
+    /// # Safety
+    ///
+    /// `v` must not contain any nul bytes.
+    pub unsafe fn from_vec_unchecked(mut v: Vec<u8>) -> CString {
+        v.reserve_exact(1);
+        v.push(0);
+        CString { inner: v.into_boxed_slice() }
+    }
+
+    /// Converts this C string into a `String` if it is valid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IntoStringError`, from which the C string can be
+    /// recovered, if the bytes are not valid UTF-8.
+    pub fn into_string(self) -> Result<String, IntoStringError> {
+        String::from_utf8(self.into_bytes()).map_err(|e| {
+            let error = e.utf8_error();
+            IntoStringError {
+                inner: unsafe { CString::from_vec_unchecked(e.into_bytes()) },
+                error,
+            }
+        })
+    }
+
+    /// Consumes this C string, returning its bytes without the trailing nul.
+    pub fn into_bytes(self) -> Vec<u8> {
+        let mut vec = self.into_bytes_with_nul();
+        vec.pop();
+        vec
+    }
+
+    /// Consumes this C string, returning its bytes including the trailing
+    /// nul.
+    pub fn into_bytes_with_nul(self) -> Vec<u8> {
+        self.inner.into_vec()
+    }
+
+    /// Returns the bytes of this C string without the trailing nul.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.inner[..self.inner.len() - 1]
+    }
+
+    /// Returns the bytes of this C string including the trailing nul.
+    pub fn as_bytes_with_nul(&self) -> &[u8] {
+        &self.inner
+    }
+
+    /// Returns a `&CStr` for the whole C string.
+    pub fn as_c_str(&self) -> &CStr {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ops::Deref for CString {
+    type Target = CStr;
+
+    fn deref(&self) -> &CStr {
+        unsafe { CStr::from_bytes_with_nul_unchecked(&self.inner) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Debug for CString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Default for CString {
+    fn default() -> CString {
+        let a: &CStr = Default::default();
+        a.to_owned()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Borrow<CStr> for CString {
+    fn borrow(&self) -> &CStr {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<CStr> for CString {
+    fn as_ref(&self) -> &CStr {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ToOwned for CStr {
+    type Owned = CString;
+
+    fn to_owned(&self) -> CString {
+        CString { inner: self.to_bytes_with_nul().into() }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&CStr> for CString {
+    fn from(s: &CStr) -> CString {
+        s.to_owned()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<CString> for Vec<u8> {
+    fn from(s: CString) -> Vec<u8> {
+        s.into_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+
+    use std::format;
+
+    #[test]
+    fn test_cstr_from_bytes_with_nul() {
+        let s = CStr::from_bytes_with_nul(b"kernel\0").unwrap();
+        assert_eq!(s.to_bytes(), b"kernel");
+        assert_eq!(s.to_bytes_with_nul(), b"kernel\0");
+        assert_eq!(s.to_str(), Ok("kernel"));
+
+        let err = CStr::from_bytes_with_nul(b"ker\0nel\0").unwrap_err();
+        assert_eq!(format!("{}", err), "data provided contains an interior nul byte at byte pos 3");
+        let err = CStr::from_bytes_with_nul(b"kernel").unwrap_err();
+        assert_eq!(format!("{}", err), "data provided is not nul terminated");
+        assert!(CStr::from_bytes_with_nul(b"").is_err());
+    }
+
+    #[test]
+    fn test_cstr_from_ptr() {
+        let bytes = b"console=ttyS0\0junk";
+        let s = unsafe { CStr::from_ptr(bytes.as_ptr() as *const c_char) };
+        assert_eq!(s.to_bytes(), b"console=ttyS0");
+        assert_eq!(s.as_ptr(), bytes.as_ptr() as *const c_char);
+        assert_eq!(unsafe { CStr::from_ptr(s.as_ptr()) }, s);
+    }
+
+    #[test]
+    fn test_cstr_debug_and_default() {
+        let s = CStr::from_bytes_with_nul(b"a\"\x01\0").unwrap();
+        assert_eq!(format!("{:?}", s), "\"a\\\"\\x01\"");
+
+        let empty: &CStr = Default::default();
+        assert_eq!(empty.to_bytes_with_nul(), b"\0");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_cstring() {
+        let s = CString::new("argv0").unwrap();
+        assert_eq!(s.as_bytes(), b"argv0");
+        assert_eq!(s.as_bytes_with_nul(), b"argv0\0");
+        assert_eq!(s.as_c_str(), CStr::from_bytes_with_nul(b"argv0\0").unwrap());
+        assert_eq!(s.as_c_str().to_owned(), s);
+        assert_eq!(s.clone().into_string().unwrap(), "argv0");
+        assert_eq!(Vec::from(s), b"argv0");
+        assert_eq!(CString::default().as_bytes_with_nul(), b"\0");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_cstring_errors() {
+        let err = CString::new(&b"a\0b"[..]).unwrap_err();
+        assert_eq!(err.nul_position(), 1);
+        assert_eq!(err.into_vec(), b"a\0b");
+
+        let err = CString::new(&[0xffu8][..]).unwrap().into_string().unwrap_err();
+        assert_eq!(err.utf8_error().valid_up_to(), 0);
+        assert_eq!(err.into_cstring().as_bytes(), &[0xff]);
+
+        let s = unsafe { CStr::from_bytes_with_nul_unchecked(b"caf\xc3\0") };
+        assert_eq!(s.to_string_lossy(), "caf\u{FFFD}");
+    }
+}
@@ -0,0 +1,123 @@
+//! A monotonic `Instant` with the same interface as `std::time::Instant`,
+//! driven by a clock function the kernel registers with `set_clock()`.
+
+use core::fmt;
+use core::mem;
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+pub use core::time::Duration;
+
+/// The registered clock function as a `usize`, or 0 if none is registered.
+static CLOCK: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets `clock` as the source of `Instant::now()`. `clock` must return the
+/// time since an arbitrary fixed point, e.g. boot, and never go backwards.
+pub fn set_clock(clock: fn() -> Duration) {
+    CLOCK.store(clock as usize, Ordering::Release);
+}
+
+/// Reads the registered clock.
+///
+/// # Panics
+///
+/// Panics if no clock has been registered with `set_clock()`.
+fn read_clock() -> Duration {
+    match CLOCK.load(Ordering::Acquire) {
+        0 => panic!("shim::time: no clock registered; call set_clock() first"),
+        // SAFETY: only `fn() -> Duration`s are ever stored in `CLOCK`.
+        clock => unsafe { mem::transmute::<usize, fn() -> Duration>(clock)() },
+    }
+}
+
+/// A measurement of a monotonically nondecreasing clock, for measuring
+/// elapsed time. See `std::time::Instant`.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant(Duration);
+
+impl Instant {
+    /// Returns an instant corresponding to "now".
+    ///
+    /// # Panics
+    ///
+    /// Panics if no clock has been registered with `set_clock()`.
+    pub fn now() -> Instant {
+        Instant(read_clock())
+    }
+
+    /// Returns the time elapsed from `earlier` to `self`, or zero if
+    /// `earlier` is later than `self`.
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        self.saturating_duration_since(earlier)
+    }
+
+    /// Returns the time elapsed from `earlier` to `self`, or `None` if
+    /// `earlier` is later than `self`.
+    pub fn checked_duration_since(&self, earlier: Instant) -> Option<Duration> {
+        self.0.checked_sub(earlier.0)
+    }
+
+    /// Returns the time elapsed from `earlier` to `self`, or zero if
+    /// `earlier` is later than `self`.
+    pub fn saturating_duration_since(&self, earlier: Instant) -> Duration {
+        self.checked_duration_since(earlier).unwrap_or_default()
+    }
+
+    /// Returns the time elapsed since this instant was created.
+    pub fn elapsed(&self) -> Duration {
+        Instant::now().duration_since(*self)
+    }
+
+    /// Returns `self + duration`, or `None` on overflow.
+    pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
+        self.0.checked_add(duration).map(Instant)
+    }
+
+    /// Returns `self - duration`, or `None` if that would precede the clock's
+    /// zero point.
+    pub fn checked_sub(&self, duration: Duration) -> Option<Instant> {
+        self.0.checked_sub(duration).map(Instant)
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Instant;
+
+    fn add(self, other: Duration) -> Instant {
+        self.checked_add(other).expect("overflow when adding duration to instant")
+    }
+}
+
+impl AddAssign<Duration> for Instant {
+    fn add_assign(&mut self, other: Duration) {
+        *self = *self + other;
+    }
+}
+
+impl Sub<Duration> for Instant {
+    type Output = Instant;
+
+    fn sub(self, other: Duration) -> Instant {
+        self.checked_sub(other).expect("overflow when subtracting duration from instant")
+    }
+}
+
+impl SubAssign<Duration> for Instant {
+    fn sub_assign(&mut self, other: Duration) {
+        *self = *self - other;
+    }
+}
+
+impl Sub<Instant> for Instant {
+    type Output = Duration;
+
+    fn sub(self, other: Instant) -> Duration {
+        self.duration_since(other)
+    }
+}
+
+impl fmt::Debug for Instant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Instant").field(&self.0).finish()
+    }
+}
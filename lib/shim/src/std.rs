@@ -1,6 +1,6 @@
 pub use std::io;
 
-#[cfg(feature = "alloc")]
 pub use std::ffi;
 #[cfg(feature = "alloc")]
 pub use std::path;
+pub use std::time;
@@ -0,0 +1,209 @@
+//! Spinning synchronization primitives, used both under `no_std` and on the
+//! host.
+//!
+//! The host does not use `std::sync` here: on the pinned toolchain,
+//! `std::sync::Mutex::new` is not a `const fn` and there is no `LazyLock`, so
+//! neither could back a `static`.
+
+use core::cell::{Cell, UnsafeCell};
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{spin_loop_hint, AtomicBool, AtomicU8, Ordering};
+
+/// A mutual exclusion primitive that spins while the lock is held.
+///
+/// There is no poisoning: a panic while the lock is held releases it, and
+/// later callers can lock it as usual.
+pub struct Mutex<T: ?Sized> {
+    lock: AtomicBool,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for Mutex<T> {}
+unsafe impl<T: ?Sized + Send> Sync for Mutex<T> {}
+
+/// A guard that releases its `Mutex` when dropped.
+pub struct MutexGuard<'a, T: ?Sized + 'a> {
+    mutex: &'a Mutex<T>,
+}
+
+impl<'a, T: ?Sized> !Send for MutexGuard<'a, T> {}
+unsafe impl<'a, T: ?Sized + Sync> Sync for MutexGuard<'a, T> {}
+
+impl<T> Mutex<T> {
+    /// Returns a new, unlocked `Mutex` holding `val`.
+    pub const fn new(val: T) -> Mutex<T> {
+        Mutex { lock: AtomicBool::new(false), data: UnsafeCell::new(val) }
+    }
+
+    /// Consumes this mutex, returning the data.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    /// Acquires the lock, spinning until it is available.
+    pub fn lock(&self) -> MutexGuard<T> {
+        loop {
+            if let Some(guard) = self.try_lock() {
+                return guard;
+            }
+
+            while self.lock.load(Ordering::Relaxed) {
+                spin_loop_hint();
+            }
+        }
+    }
+
+    /// Acquires the lock if it is available, returning `None` otherwise.
+    pub fn try_lock(&self) -> Option<MutexGuard<T>> {
+        match self.lock.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed) {
+            Ok(_) => Some(MutexGuard { mutex: self }),
+            Err(_) => None,
+        }
+    }
+
+    /// Returns a mutable reference to the data. No locking is needed since
+    /// the borrow is exclusive.
+    pub fn get_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.data.get() }
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Mutex<T> {
+        Mutex::new(T::default())
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.try_lock() {
+            Some(guard) => f.debug_struct("Mutex").field("data", &&*guard).finish(),
+            None => f.debug_struct("Mutex").field("data", &"<locked>").finish(),
+        }
+    }
+}
+
+impl<'a, T: ?Sized> Deref for MutexGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<'a, T: ?Sized> DerefMut for MutexGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<'a, T: ?Sized> Drop for MutexGuard<'a, T> {
+    fn drop(&mut self) {
+        self.mutex.lock.store(false, Ordering::Release);
+    }
+}
+
+const INCOMPLETE: u8 = 0;
+const RUNNING: u8 = 1;
+const COMPLETE: u8 = 2;
+
+/// A primitive to run a one-time initialization.
+pub struct Once {
+    state: AtomicU8,
+}
+
+impl Once {
+    /// Returns a new `Once` that has not run yet.
+    pub const fn new() -> Once {
+        Once { state: AtomicU8::new(INCOMPLETE) }
+    }
+
+    /// Runs `f` if no call to `call_once` has run its closure yet. If another
+    /// call is running its closure, spins until it has finished. When this
+    /// returns, the initialization has completed.
+    ///
+    /// Unlike `std::sync::Once`, there is no poisoning: if `f` panics, later
+    /// calls spin forever.
+    pub fn call_once<F: FnOnce()>(&self, f: F) {
+        if self.is_completed() {
+            return;
+        }
+
+        match self.state.compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire) {
+            Ok(_) => {
+                f();
+                self.state.store(COMPLETE, Ordering::Release);
+            }
+            Err(_) => {
+                while !self.is_completed() {
+                    spin_loop_hint();
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if a `call_once` has completed.
+    pub fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+}
+
+impl fmt::Debug for Once {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Once").finish()
+    }
+}
+
+/// A value initialized on first access by the function given to `new`.
+pub struct Lazy<T, F = fn() -> T> {
+    once: Once,
+    init: Cell<Option<F>>,
+    value: UnsafeCell<Option<T>>,
+}
+
+unsafe impl<T: Send + Sync, F: Send> Sync for Lazy<T, F> {}
+
+impl<T, F> Lazy<T, F> {
+    /// Returns a new `Lazy` that will be initialized with `f()`.
+    pub const fn new(f: F) -> Lazy<T, F> {
+        Lazy { once: Once::new(), init: Cell::new(Some(f)), value: UnsafeCell::new(None) }
+    }
+}
+
+impl<T, F: FnOnce() -> T> Lazy<T, F> {
+    /// Forces the evaluation of `this`, returning a reference to the value.
+    pub fn force(this: &Lazy<T, F>) -> &T {
+        this.once.call_once(|| {
+            let f = this.init.take().expect("Lazy initializer already taken");
+            unsafe { *this.value.get() = Some(f()) };
+        });
+
+        match unsafe { &*this.value.get() } {
+            Some(value) => value,
+            None => unreachable!("Lazy initialized"),
+        }
+    }
+}
+
+impl<T, F: FnOnce() -> T> Deref for Lazy<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        Lazy::force(self)
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for Lazy<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.once.is_completed() {
+            true => match unsafe { &*self.value.get() } {
+                Some(value) => f.debug_tuple("Lazy").field(value).finish(),
+                None => unreachable!("Lazy initialized"),
+            },
+            false => f.write_str("Lazy(<uninit>)"),
+        }
+    }
+}
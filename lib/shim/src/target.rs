@@ -0,0 +1,76 @@
+//! Constants describing the board being built for. The board is selected by
+//! cargo feature: `pi4` for the Raspberry Pi 4, `qemu` for QEMU's `raspi3`
+//! machine, and the Raspberry Pi 3 if neither is enabled.
+
+#[cfg(all(feature = "pi4", feature = "qemu"))]
+compile_error!("at most one of the `pi4` and `qemu` features may be enabled");
+
+/// A board this crate can be built for.
+///
+/// The per-board values below are `const fn`s, which cannot branch on the
+/// pinned toolchain. Each looks its value up in an array indexed by
+/// `self as usize`, in the order the variants are declared here.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Board {
+    /// The Raspberry Pi 3 (BCM2837).
+    Pi3,
+    /// The Raspberry Pi 4 (BCM2711), in low peripheral mode.
+    Pi4,
+    /// QEMU's `raspi3` machine.
+    Qemu,
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "pi4")] {
+        /// The board being built for.
+        pub const BOARD: Board = Board::Pi4;
+    } else if #[cfg(feature = "qemu")] {
+        /// The board being built for.
+        pub const BOARD: Board = Board::Qemu;
+    } else {
+        /// The board being built for.
+        pub const BOARD: Board = Board::Pi3;
+    }
+}
+
+impl Board {
+    /// Returns the address where I/O peripherals are mapped to.
+    pub const fn io_base(self) -> usize {
+        // Pi3, Pi4, Qemu.
+        [0x3F00_0000, 0xFE00_0000, 0x3F00_0000][self as usize]
+    }
+
+    /// Returns the number of CPU cores.
+    pub const fn num_cores(self) -> usize {
+        4
+    }
+
+    /// Returns the frequency of the VPU core clock, which clocks the mini UART,
+    /// in Hz.
+    pub const fn core_clock_hz(self) -> u64 {
+        // Pi3, Pi4, Qemu.
+        [250 * 1000 * 1000, 500 * 1000 * 1000, 250 * 1000 * 1000][self as usize]
+    }
+
+    /// Returns the frequency of the ARM generic timer (`CNTFRQ_EL0`) in Hz.
+    pub const fn generic_timer_hz(self) -> u64 {
+        // Pi3, Pi4, Qemu.
+        [19_200_000, 54_000_000, 62_500_000][self as usize]
+    }
+}
+
+/// The address where I/O peripherals are mapped to.
+pub const IO_BASE: usize = BOARD.io_base();
+
+/// The number of CPU cores.
+pub const NUM_CORES: usize = BOARD.num_cores();
+
+/// The frequency of the VPU core clock in Hz.
+pub const CORE_CLOCK_HZ: u64 = BOARD.core_clock_hz();
+
+/// The frequency of the ARM generic timer in Hz.
+pub const GENERIC_TIMER_HZ: u64 = BOARD.generic_timer_hz();
+
+/// The frequency of the system timer (`pi::timer`) in Hz. It is the same on
+/// every board.
+pub const SYSTEM_TIMER_HZ: u64 = 1_000_000;
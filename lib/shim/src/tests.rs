@@ -9,4 +9,141 @@ mod assert {
         const_assert_size!(S2, 2+2);
         S2(2, 2);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_assert_offset() {
+        #[repr(C)]
+        #[allow(non_snake_case)]
+        struct Registers {
+            IO: u32,
+            IER: u32,
+            _reserved: [u32; 3],
+            LSR: u32,
+        }
+
+        assert_offset!(Registers, IO, 0x00);
+        assert_offset!(Registers, IER, 0x04);
+        assert_offset!(Registers, LSR, 0x14);
+        const_assert_size!(Registers, 0x18);
+    }
+
+    #[test]
+    fn test_const_assert_offset() {
+        #[repr(C)]
+        #[allow(non_snake_case)]
+        struct Registers {
+            IO: u32,
+            IER: u32,
+            _reserved: [u32; 3],
+            LSR: u32,
+        }
+
+        const_assert_offset!(Registers, IO, 0x00);
+        const_assert_offset!(Registers, IER, 0x04);
+        const_assert_offset!(Registers, LSR, 0x14);
+        assert_eq!(field_offset!(Registers, LSR), 0x14);
+    }
+
+    #[test]
+    fn test_const_assert_align() {
+        #[repr(C, align(32))]
+        struct Aligned(u8);
+        const_assert_align!(Aligned, 32);
+        const_assert_align!(u64, 8);
+        Aligned(0);
+    }
+}
+mod hexdump {
+    use crate::hexdump::HexDump;
+
+    #[test]
+    fn test_hexdump() {
+        assert_eq!(format!("{}", HexDump(&[])), "");
+        assert_eq!(
+            format!("{}", HexDump(b"Hello, world!\n")),
+            "00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a        |Hello, world!.|"
+        );
+
+        let bytes: Vec<u8> = (0..20).collect();
+        assert_eq!(
+            format!("{:?}", HexDump(&bytes)),
+            "00000000  00 01 02 03 04 05 06 07  08 09 0a 0b 0c 0d 0e 0f  |................|\n\
+             00000010  10 11 12 13                                       |....|"
+        );
+    }
+}
+
+mod sync {
+    use crate::sync::{Lazy, Mutex, Once};
+
+    static COUNTER: Mutex<u32> = Mutex::new(0);
+    static TABLE: Lazy<[u32; 4]> = Lazy::new(|| [1, 2, 4, 8]);
+
+    #[test]
+    fn test_mutex() {
+        *COUNTER.lock() += 1;
+        let guard = COUNTER.lock();
+        assert!(COUNTER.try_lock().is_none());
+        assert_eq!(*guard, 1);
+        drop(guard);
+
+        let mut mutex = Mutex::new(vec![1]);
+        mutex.get_mut().push(2);
+        assert_eq!(mutex.into_inner(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_mutex_ignores_poison() {
+        let mutex = std::sync::Arc::new(Mutex::new(1));
+        let clone = mutex.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = clone.lock();
+            panic!("poison the lock");
+        }).join();
+
+        assert_eq!(*mutex.lock(), 1);
+    }
+
+    #[test]
+    fn test_once_and_lazy() {
+        let once = Once::new();
+        let mut runs = 0;
+        once.call_once(|| runs += 1);
+        once.call_once(|| runs += 1);
+        assert_eq!(runs, 1);
+        assert!(once.is_completed());
+
+        assert_eq!(TABLE[3], 8);
+        assert_eq!(Lazy::force(&TABLE).len(), 4);
+    }
+}
+
+mod ioerr {
+    use std::io;
+
+    #[test]
+    fn test_ioerr_format() {
+        let sector = 7;
+        let err = newioerr!(InvalidData, "bad sector {}", sector);
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        #[cfg(any(feature = "alloc", not(feature = "no_std")))]
+        assert_eq!(err.to_string(), "bad sector 7");
+        #[cfg(all(feature = "no_std", not(feature = "alloc")))]
+        assert_eq!(err.to_string(), "bad sector {}");
+
+        let res: io::Result<()> = ioerr!(NotFound, "no cluster {:#x}", 0x20);
+        assert_eq!(res.unwrap_err().kind(), io::ErrorKind::NotFound);
+
+        let res: io::Result<()> = ioerr!(Other, "static");
+        assert_eq!(res.unwrap_err().to_string(), "static");
+    }
+
+    #[test]
+    fn test_ioerr_evaluates_args() {
+        let mut calls = 0;
+        let mut next = || { calls += 1; calls };
+        let _ = newioerr!(Other, "{} {}", next(), next());
+        let _: io::Result<()> = ioerr!(Other, "{}", next());
+        assert_eq!(calls, 3);
+    }
+}
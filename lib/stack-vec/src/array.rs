@@ -0,0 +1,36 @@
+use core::mem::MaybeUninit;
+
+/// Fixed-size arrays `[T; N]` usable as inline storage or as chunks.
+///
+/// The pinned toolchain predates const generics, so this trait stands in for
+/// a `const N: usize` parameter. It is implemented for arrays of length 0 to
+/// 32 and for powers of two up to 65536.
+///
+/// # Safety
+///
+/// Implementors must be `[Self::Item; Self::LEN]`, and `Self::Uninit` must be
+/// `[MaybeUninit<Self::Item>; Self::LEN]`.
+pub unsafe trait Array {
+    /// The type of the array's elements.
+    type Item;
+
+    /// The same array with every element wrapped in `MaybeUninit`.
+    type Uninit;
+
+    /// The number of elements in the array.
+    const LEN: usize;
+}
+
+macro_rules! impl_array {
+    ($($n:expr),*) => ($(
+        unsafe impl<T> Array for [T; $n] {
+            type Item = T;
+            type Uninit = [MaybeUninit<T>; $n];
+            const LEN: usize = $n;
+        }
+    )*)
+}
+
+impl_array!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+            17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32,
+            64, 128, 256, 512, 1024, 2048, 4096, 8192, 16384, 32768, 65536);
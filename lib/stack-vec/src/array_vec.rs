@@ -0,0 +1,424 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::iter::FromIterator;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ops::{Deref, DerefMut, RangeBounds};
+use core::slice;
+
+use crate::{chunks, Array, Drain, Pod, StackVec};
+
+/// A contiguous array type with inline, fixed-capacity storage.
+///
+/// `ArrayVec` is the owned counterpart of `StackVec`: instead of borrowing a
+/// user-supplied slice, it stores its values inline in an array of type `A`.
+/// An `ArrayVec<[T; N]>` holds up to `N` values of type `T`. It has no
+/// lifetime parameter, so it can be a field of another struct. Its API mirrors
+/// that of `StackVec`; `push` fails once `N` values are held.
+pub struct ArrayVec<A: Array> {
+    storage: MaybeUninit<A>,
+    len: usize,
+}
+
+/// Returns the slots of `storage` as a slice.
+fn slots_mut<A: Array>(storage: &mut MaybeUninit<A>) -> &mut [MaybeUninit<A::Item>] {
+    // SAFETY: `A` is `[A::Item; A::LEN]`, and `MaybeUninit` has the same
+    // layout as the type it wraps.
+    unsafe { slice::from_raw_parts_mut(storage.as_mut_ptr() as *mut MaybeUninit<A::Item>, A::LEN) }
+}
+
+/// A `StackVec` borrowing an `ArrayVec`'s storage. The vector's length is
+/// written back to the `ArrayVec` when the view is dropped.
+struct View<'a, T: 'a> {
+    len: &'a mut usize,
+    vec: ManuallyDrop<StackVec<'a, T>>,
+}
+
+impl<'a, T: 'a> Deref for View<'a, T> {
+    type Target = StackVec<'a, T>;
+
+    fn deref(&self) -> &StackVec<'a, T> {
+        &self.vec
+    }
+}
+
+impl<'a, T: 'a> DerefMut for View<'a, T> {
+    fn deref_mut(&mut self) -> &mut StackVec<'a, T> {
+        &mut self.vec
+    }
+}
+
+impl<'a, T: 'a> Drop for View<'a, T> {
+    fn drop(&mut self) {
+        // The `ArrayVec` owns the elements again; the view must not drop them.
+        *self.len = self.vec.len();
+    }
+}
+
+impl<A: Array> ArrayVec<A> {
+    /// Constructs a new, empty `ArrayVec<[T; N]>`. The returned `ArrayVec`
+    /// will be able to hold `N` values.
+    pub fn new() -> ArrayVec<A> {
+        ArrayVec { storage: MaybeUninit::uninit(), len: 0 }
+    }
+
+    /// Returns a `StackVec` view of this vector to implement operations with.
+    fn view(&mut self) -> View<'_, A::Item> {
+        // SAFETY: exactly the first `len` elements are initialized. The view
+        // owns them until it is dropped, after which `self` owns them again.
+        let vec = unsafe { StackVec::from_uninit_with_len(slots_mut(&mut self.storage), self.len) };
+        View { len: &mut self.len, vec: ManuallyDrop::new(vec) }
+    }
+
+    /// Returns the number of elements this vector can hold, `N`.
+    pub fn capacity(&self) -> usize {
+        A::LEN
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping the
+    /// rest. If `len` is greater than the vector's current length, this has no
+    /// effect.
+    pub fn truncate(&mut self, len: usize) {
+        self.view().truncate(len)
+    }
+
+    /// Removes and drops all elements of the vector.
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Extracts a slice containing the entire vector.
+    pub fn as_slice(&self) -> &[A::Item] {
+        // SAFETY: the first `len` elements are initialized.
+        unsafe { slice::from_raw_parts(self.storage.as_ptr() as *const A::Item, self.len) }
+    }
+
+    /// Extracts a mutable slice of the entire vector.
+    pub fn as_mut_slice(&mut self) -> &mut [A::Item] {
+        // SAFETY: the first `len` elements are initialized.
+        unsafe { slice::from_raw_parts_mut(self.storage.as_mut_ptr() as *mut A::Item, self.len) }
+    }
+
+    /// Returns the number of elements in the vector, also referred to as its
+    /// 'length'.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the vector contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if the vector is at capacity.
+    pub fn is_full(&self) -> bool {
+        self.len == A::LEN
+    }
+
+    /// Appends `value` to the back of this vector if the vector is not full.
+    ///
+    /// # Error
+    ///
+    /// If this vector is full, an `Err` is returned. Otherwise, `Ok` is
+    /// returned.
+    pub fn push(&mut self, value: A::Item) -> Result<(), ()> {
+        self.view().push(value)
+    }
+
+    /// If this vector is not empty, removes the last element from this vector
+    /// and returns it. Otherwise returns `None`.
+    pub fn pop(&mut self) -> Option<A::Item> {
+        self.view().pop()
+    }
+
+    /// Inserts `value` at position `index`, shifting all elements after it to
+    /// the right.
+    ///
+    /// # Error
+    ///
+    /// If this vector is full, an `Err` is returned and `value` is dropped.
+    /// Otherwise, `Ok` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len()`.
+    pub fn insert(&mut self, index: usize, value: A::Item) -> Result<(), ()> {
+        self.view().insert(index, value)
+    }
+
+    /// Binary searches this sorted vector with a comparator function. See
+    /// `slice::binary_search_by()`.
+    pub fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&A::Item) -> Ordering,
+    {
+        self.as_slice().binary_search_by(f)
+    }
+
+    /// Appends every value yielded by `iter` to the back of this vector. See
+    /// `StackVec::try_extend()`.
+    ///
+    /// # Error
+    ///
+    /// If the vector fills up before the iterator is exhausted, an `Err` is
+    /// returned. Otherwise, `Ok` is returned with the number of values that
+    /// were pushed.
+    pub fn try_extend<I: IntoIterator<Item = A::Item>>(&mut self, iter: I) -> Result<usize, ()> {
+        self.view().try_extend(iter)
+    }
+
+    /// Removes the elements in `range` from the vector, returning them by value
+    /// from an iterator. See `StackVec::drain()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end, or if the end
+    /// of the range is greater than the length of the vector.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, A::Item> {
+        Drain::new(slots_mut(&mut self.storage), &mut self.len, range)
+    }
+
+    /// Retains only the elements for which `f` returns `true`, removing the
+    /// rest. The retained elements keep their relative order.
+    pub fn retain<F: FnMut(&A::Item) -> bool>(&mut self, f: F) {
+        self.view().retain(f)
+    }
+
+    /// Removes the element at `index` and returns it, replacing it with the
+    /// last element of the vector. This does not preserve ordering, but is
+    /// O(1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> A::Item {
+        self.view().swap_remove(index)
+    }
+
+    /// Returns the unused tail of the storage. See
+    /// `StackVec::spare_capacity_mut()`.
+    ///
+    /// Unlike the `StackVec` method, this is safe: an `ArrayVec` owns its
+    /// storage, which nothing outside the vector reads as initialized `A::Item`s.
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<A::Item>] {
+        &mut slots_mut(&mut self.storage)[self.len..]
+    }
+
+    /// Returns the elements of the vector and the unused tail of the storage
+    /// at once. Safe for the same reason as `spare_capacity_mut()`.
+    pub fn split_at_spare_mut(&mut self) -> (&mut [A::Item], &mut [MaybeUninit<A::Item>]) {
+        let (elements, spare) = slots_mut(&mut self.storage).split_at_mut(self.len);
+        // SAFETY: the first `len` elements are initialized.
+        let elements = unsafe { &mut *(elements as *mut [MaybeUninit<A::Item>] as *mut [A::Item]) };
+        (elements, spare)
+    }
+
+    /// Sets the length of the vector to `len`. See `StackVec::assume_len()`.
+    ///
+    /// # Safety
+    ///
+    /// The first `len` elements of the storage must be initialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is greater than `N`.
+    pub unsafe fn assume_len(&mut self, len: usize) {
+        if len > A::LEN {
+            panic!("ArrayVec::assume_len(): len {} exceeds capacity {}", len, A::LEN);
+        }
+
+        self.len = len;
+    }
+}
+
+impl<A: Array> ArrayVec<A> {
+    /// Splits the vector into arrays of type `C` and a remainder shorter than
+    /// the array length. See `StackVec::as_chunks()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `C` is a zero-length array.
+    pub fn as_chunks<C: Array<Item = A::Item>>(&self) -> (&[C], &[A::Item]) {
+        chunks::as_chunks(self.as_slice())
+    }
+
+    /// Mutable version of `as_chunks()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `C` is a zero-length array.
+    pub fn as_chunks_mut<C: Array<Item = A::Item>>(&mut self) -> (&mut [C], &mut [A::Item]) {
+        chunks::as_chunks_mut(self.as_mut_slice())
+    }
+}
+
+impl<A: Array<Item = u8>> ArrayVec<A> {
+    /// Views the bytes of this vector as a prefix of bytes, the largest run of
+    /// correctly aligned `U`s, and a suffix of bytes. See
+    /// `StackVec::align_to()`.
+    pub fn align_to<U: Pod>(&self) -> (&[u8], &[U], &[u8]) {
+        chunks::align_to(self.as_slice())
+    }
+
+    /// Mutable version of `align_to()`.
+    pub fn align_to_mut<U: Pod>(&mut self) -> (&mut [u8], &mut [U], &mut [u8]) {
+        chunks::align_to_mut(self.as_mut_slice())
+    }
+}
+
+impl<A: Array> ArrayVec<A>
+where
+    A::Item: Pod,
+{
+    /// Zeroes the unused tail of the storage, passes it to `f`, and appends
+    /// the values `f` reports having written. See `StackVec::fill_spare()`.
+    ///
+    /// # Error
+    ///
+    /// If `f` returns an `Err`, the vector is left unchanged and the error is
+    /// returned. Otherwise, `Ok(n)` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the length of the slice passed to `f`.
+    pub fn fill_spare<E, F>(&mut self, f: F) -> Result<usize, E>
+    where
+        F: FnOnce(&mut [A::Item]) -> Result<usize, E>,
+    {
+        self.view().fill_spare(f)
+    }
+}
+
+impl<A: Array> ArrayVec<A>
+where
+    A::Item: PartialEq,
+{
+    /// Removes consecutive repeated elements, keeping the first of each run.
+    /// If the vector is sorted, this removes all duplicates.
+    pub fn dedup(&mut self) {
+        self.view().dedup()
+    }
+}
+
+impl<A: Array> ArrayVec<A>
+where
+    A::Item: Ord,
+{
+    /// Inserts `value` into this sorted vector, keeping it sorted. See
+    /// `StackVec::insert_sorted()`.
+    ///
+    /// # Error
+    ///
+    /// If this vector is full, an `Err` is returned and `value` is dropped.
+    /// Otherwise, `Ok` is returned with the index `value` was inserted at.
+    pub fn insert_sorted(&mut self, value: A::Item) -> Result<usize, ()> {
+        self.view().insert_sorted(value)
+    }
+}
+
+impl<A: Array> ArrayVec<A>
+where
+    A::Item: Clone,
+{
+    /// Appends clones of all of the elements in `other` to the back of this
+    /// vector if they all fit.
+    ///
+    /// # Error
+    ///
+    /// If there is not enough room for all of `other`, an `Err` is returned and
+    /// the vector is left unchanged. Otherwise, `Ok` is returned with the
+    /// number of elements that were copied, `other.len()`.
+    pub fn extend_from_slice(&mut self, other: &[A::Item]) -> Result<usize, ()> {
+        self.view().extend_from_slice(other)
+    }
+}
+
+/// Appends every value yielded by the iterator, like `try_extend()`.
+///
+/// # Panics
+///
+/// Panics if the vector fills up before the iterator is exhausted. Use
+/// `try_extend()` to handle running out of space.
+impl<A: Array> Extend<A::Item> for ArrayVec<A> {
+    fn extend<I: IntoIterator<Item = A::Item>>(&mut self, iter: I) {
+        if self.try_extend(iter).is_err() {
+            panic!("ArrayVec::extend(): capacity {} exceeded", A::LEN);
+        }
+    }
+}
+
+/// Collects the iterator into a new `ArrayVec`.
+///
+/// # Panics
+///
+/// Panics if the iterator yields more than `N` values.
+impl<A: Array> FromIterator<A::Item> for ArrayVec<A> {
+    fn from_iter<I: IntoIterator<Item = A::Item>>(iter: I) -> ArrayVec<A> {
+        let mut vec = ArrayVec::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<A: Array> Drop for ArrayVec<A> {
+    fn drop(&mut self) {
+        self.truncate(0);
+    }
+}
+
+impl<A: Array> Default for ArrayVec<A> {
+    fn default() -> ArrayVec<A> {
+        ArrayVec::new()
+    }
+}
+
+impl<A: Array> Clone for ArrayVec<A>
+where
+    A::Item: Clone,
+{
+    fn clone(&self) -> ArrayVec<A> {
+        let mut vec = ArrayVec::new();
+        vec.extend_from_slice(self).expect("clone has the same capacity");
+        vec
+    }
+}
+
+impl<A: Array> Deref for ArrayVec<A> {
+    type Target = [A::Item];
+
+    fn deref(&self) -> &[A::Item] {
+        self.as_slice()
+    }
+}
+
+impl<A: Array> DerefMut for ArrayVec<A> {
+    fn deref_mut(&mut self) -> &mut [A::Item] {
+        self.as_mut_slice()
+    }
+}
+
+impl<A: Array> fmt::Debug for ArrayVec<A>
+where
+    A::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'b, A: Array> IntoIterator for &'b ArrayVec<A> {
+    type Item = &'b A::Item;
+    type IntoIter = slice::Iter<'b, A::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'b, A: Array> IntoIterator for &'b mut ArrayVec<A> {
+    type Item = &'b mut A::Item;
+    type IntoIter = slice::IterMut<'b, A::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
@@ -0,0 +1,87 @@
+use core::mem::MaybeUninit;
+use core::ptr;
+use core::slice;
+
+use crate::Array;
+
+/// Types for which every bit pattern is a valid value and which contain no
+/// padding, so they can be safely viewed as and built from raw bytes.
+///
+/// # Safety
+///
+/// Implementors must be `#[repr(C)]` or primitive, contain no padding bytes,
+/// no pointers or references, and accept any bit pattern as a valid value.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => ($(unsafe impl Pod for $t {})*)
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_pod_array {
+    ($($n:expr),*) => ($(unsafe impl<T: Pod> Pod for [T; $n] {})*)
+}
+
+impl_pod_array!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+                17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32);
+
+/// Splits `slice` into arrays of type `A`, starting at the beginning, and a
+/// remainder shorter than `A::LEN`.
+///
+/// # Panics
+///
+/// Panics if `A::LEN` is 0.
+pub(crate) fn as_chunks<A: Array>(slice: &[A::Item]) -> (&[A], &[A::Item]) {
+    if A::LEN == 0 {
+        panic!("as_chunks(): chunk size must be non-zero");
+    }
+
+    let count = slice.len() / A::LEN;
+    let (chunks, rest) = slice.split_at(count * A::LEN);
+    // SAFETY: `A` is `[A::Item; A::LEN]`, which has the same layout as
+    // `A::LEN` consecutive items, and `chunks` holds exactly
+    // `count * A::LEN` items.
+    let chunks = unsafe { slice::from_raw_parts(chunks.as_ptr() as *const A, count) };
+    (chunks, rest)
+}
+
+/// Mutable version of `as_chunks()`.
+///
+/// # Panics
+///
+/// Panics if `A::LEN` is 0.
+pub(crate) fn as_chunks_mut<A: Array>(slice: &mut [A::Item]) -> (&mut [A], &mut [A::Item]) {
+    if A::LEN == 0 {
+        panic!("as_chunks_mut(): chunk size must be non-zero");
+    }
+
+    let count = slice.len() / A::LEN;
+    let (chunks, rest) = slice.split_at_mut(count * A::LEN);
+    // SAFETY: as in `as_chunks()`.
+    let chunks = unsafe { slice::from_raw_parts_mut(chunks.as_mut_ptr() as *mut A, count) };
+    (chunks, rest)
+}
+
+/// Views `bytes` as a prefix of bytes, the largest run of correctly aligned
+/// `U`s, and a suffix of bytes.
+pub(crate) fn align_to<U: Pod>(bytes: &[u8]) -> (&[u8], &[U], &[u8]) {
+    // SAFETY: `U` is `Pod`, so any bytes form a valid `U`.
+    unsafe { bytes.align_to() }
+}
+
+/// Mutable version of `align_to()`.
+pub(crate) fn align_to_mut<U: Pod>(bytes: &mut [u8]) -> (&mut [u8], &mut [U], &mut [u8]) {
+    // SAFETY: `U` is `Pod`, so any bytes form a valid `U`, and any `U` is
+    // valid as bytes since it has no padding.
+    unsafe { bytes.align_to_mut() }
+}
+
+/// Zeroes `slots` and returns them as initialized values.
+pub(crate) fn zeroed<T: Pod>(slots: &mut [MaybeUninit<T>]) -> &mut [T] {
+    // SAFETY: `T` is `Pod`, so all zeroes is a valid `T`.
+    unsafe {
+        ptr::write_bytes(slots.as_mut_ptr(), 0, slots.len());
+        &mut *(slots as *mut [MaybeUninit<T>] as *mut [T])
+    }
+}
@@ -0,0 +1,135 @@
+use core::iter::FusedIterator;
+use core::mem::MaybeUninit;
+use core::ops::{Bound, RangeBounds};
+use core::ptr;
+use core::slice;
+
+/// A draining iterator for `StackVec` and `ArrayVec`, created by
+/// [`StackVec::drain()`] and [`ArrayVec::drain()`].
+///
+/// Yields the drained elements by value. When dropped, any elements that were
+/// not yielded are dropped and the elements after the drained range are
+/// shifted down to close the gap.
+///
+/// [`ArrayVec::drain()`]: crate::ArrayVec::drain
+pub struct Drain<'v, T: 'v> {
+    storage: &'v mut [MaybeUninit<T>],
+    /// The length of the vector being drained.
+    len: &'v mut usize,
+    /// Index of the next element to yield from the front.
+    front: usize,
+    /// One past the index of the next element to yield from the back.
+    back: usize,
+    /// Index of the first element after the drained range.
+    tail_start: usize,
+    /// Number of elements after the drained range.
+    tail_len: usize,
+}
+
+impl<'v, T: 'v> Drain<'v, T> {
+    /// Creates a draining iterator over `range` of the vector whose first
+    /// `*len` elements of `storage` are initialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end, or if the end
+    /// of the range is greater than `*len`.
+    pub(crate) fn new<R: RangeBounds<usize>>(
+        storage: &'v mut [MaybeUninit<T>],
+        len: &'v mut usize,
+        range: R,
+    ) -> Self {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.checked_add(1).expect("drain start overflows usize"),
+            Bound::Unbounded => 0,
+        };
+
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n.checked_add(1).expect("drain end overflows usize"),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => *len,
+        };
+
+        if start > end {
+            panic!("drain start (is {}) should be <= end (is {})", start, end);
+        }
+
+        if end > *len {
+            panic!("drain end (is {}) should be <= len (is {})", end, *len);
+        }
+
+        let tail_len = *len - end;
+        // Until the drain is dropped, the vector only owns the elements before
+        // the drained range. Leaking the `Drain` leaks the rest, but never
+        // exposes moved-out slots.
+        *len = start;
+        Drain {
+            storage,
+            len,
+            front: start,
+            back: end,
+            tail_start: end,
+            tail_len,
+        }
+    }
+
+    /// Returns the remaining, not yet yielded elements as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        let first = self.storage[self.front..].as_ptr() as *const T;
+        // SAFETY: the elements in `front..back` are initialized.
+        unsafe { slice::from_raw_parts(first, self.back - self.front) }
+    }
+}
+
+impl<'v, T: 'v> Iterator for Drain<'v, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+
+        let index = self.front;
+        self.front += 1;
+        // SAFETY: `index` is initialized and has not been yielded before.
+        Some(unsafe { ptr::read(self.storage[index].as_ptr()) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<'v, T: 'v> DoubleEndedIterator for Drain<'v, T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.back -= 1;
+        // SAFETY: `back` is initialized and has not been yielded before.
+        Some(unsafe { ptr::read(self.storage[self.back].as_ptr()) })
+    }
+}
+
+impl<'v, T: 'v> ExactSizeIterator for Drain<'v, T> {}
+
+impl<'v, T: 'v> FusedIterator for Drain<'v, T> {}
+
+impl<'v, T: 'v> Drop for Drain<'v, T> {
+    fn drop(&mut self) {
+        // Drop the elements that were never yielded.
+        self.for_each(drop);
+
+        let start = *self.len;
+        if self.tail_len > 0 && start != self.tail_start {
+            let base = self.storage.as_mut_ptr();
+            // SAFETY: both ranges are within `storage`; they may overlap.
+            unsafe { ptr::copy(base.add(self.tail_start), base.add(start), self.tail_len) }
+        }
+
+        *self.len = start + self.tail_len;
+    }
+}
@@ -1,11 +1,27 @@
 #![no_std]
+#![allow(clippy::result_unit_err)]
 
 #[cfg(test)]
 mod tests;
+mod array;
+mod array_vec;
+mod chunks;
+mod drain;
+mod stack_string;
 
+pub use array::Array;
+pub use array_vec::ArrayVec;
+pub use chunks::Pod;
+pub use drain::Drain;
+pub use stack_string::StackString;
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::mem::{self, MaybeUninit};
+use core::ptr;
 use core::slice;
 use core::iter::IntoIterator;
-use core::ops::{Deref, DerefMut};
+use core::ops::{Deref, DerefMut, RangeBounds};
 
 /// A contiguous array type backed by a slice.
 ///
@@ -15,18 +31,43 @@ use core::ops::{Deref, DerefMut};
 /// result, `StackVec`'s capacity is _bounded_ by the user-supplied slice. This
 /// results in `push` being fallible: if `push` is called when the vector is
 /// full, an `Err` is returned.
-#[derive(Debug)]
+///
+/// Only the first `len()` slots of the backing slice hold values owned by the
+/// vector. Values are moved in by `push` and moved out by `pop`, so `T` need
+/// not be `Clone`. Values still in the vector when it is dropped are dropped
+/// with it.
 pub struct StackVec<'a, T: 'a> {
-    storage: &'a mut [T],
+    storage: &'a mut [MaybeUninit<T>],
     len: usize
 }
 
-impl<'a, T: 'a> StackVec<'a, T> {
+/// Returns an array of uninitialized slots the size of `A`, suitable as the
+/// backing store for `StackVec::from_uninit()`, without writing to any of
+/// them.
+///
+/// ```rust
+/// use stack_vec::{uninit_storage, StackVec};
+///
+/// let mut storage = uninit_storage::<[u8; 4096]>();
+/// let mut vec = StackVec::from_uninit(&mut storage);
+/// vec.push(1).unwrap();
+/// ```
+#[allow(clippy::uninit_assumed_init)]
+pub fn uninit_storage<A: Array>() -> A::Uninit {
+    // SAFETY: `A::Uninit` is an array of `MaybeUninit`, which requires no
+    // initialization.
+    unsafe { MaybeUninit::uninit().assume_init() }
+}
+
+impl<'a, T: Copy + 'a> StackVec<'a, T> {
     /// Constructs a new, empty `StackVec<T>` using `storage` as the backing
     /// store. The returned `StackVec` will be able to hold `storage.len()`
     /// values.
+    ///
+    /// `T` must be `Copy` since values moved out of the vector leave a copy
+    /// behind in `storage`. Use `from_uninit` for other types.
     pub fn new(storage: &'a mut [T]) -> StackVec<'a, T> {
-        unimplemented!()
+        StackVec::with_len(storage, 0)
     }
 
     /// Constructs a new `StackVec<T>` using `storage` as the backing store. The
@@ -38,53 +79,138 @@ impl<'a, T: 'a> StackVec<'a, T> {
     ///
     /// Panics if `len > storage.len()`.
     pub fn with_len(storage: &'a mut [T], len: usize) -> StackVec<'a, T> {
-        unimplemented!()
+        if len > storage.len() {
+            panic!("StackVec::with_len(): len {} exceeds capacity {}", len, storage.len());
+        }
+
+        // SAFETY: `MaybeUninit<T>` has the same layout as `T`. Only initialized
+        // values are ever written through the returned slice, and since `T` is
+        // `Copy`, the values left behind in `storage` are always valid.
+        let storage = unsafe { &mut *(storage as *mut [T] as *mut [MaybeUninit<T>]) };
+        StackVec { storage, len }
+    }
+}
+
+impl<'a, T: 'a> StackVec<'a, T> {
+    /// Constructs a new, empty `StackVec<T>` using the uninitialized `storage`
+    /// as the backing store. The returned `StackVec` will be able to hold
+    /// `storage.len()` values of any type.
+    ///
+    /// Unlike `new`, `storage` need not be filled with placeholder values
+    /// first; see `uninit_storage()`.
+    pub fn from_uninit(storage: &'a mut [MaybeUninit<T>]) -> StackVec<'a, T> {
+        StackVec { storage, len: 0 }
+    }
+
+    /// Constructs a new `StackVec<T>` using the uninitialized `storage` as the
+    /// backing store and fills it with values from `iter` until either the
+    /// iterator is exhausted or the vector is full.
+    ///
+    /// The rest of the iterator is returned alongside the vector. No value is
+    /// taken from it once the vector is full, so it yields exactly the values
+    /// that did not fit.
+    pub fn from_iter_with_storage<I: IntoIterator<Item = T>>(
+        storage: &'a mut [MaybeUninit<T>],
+        iter: I
+    ) -> (StackVec<'a, T>, I::IntoIter) {
+        let mut vec = StackVec::from_uninit(storage);
+        let mut iter = iter.into_iter();
+        while !vec.is_full() {
+            match iter.next() {
+                Some(value) => vec.storage[vec.len] = MaybeUninit::new(value),
+                None => break,
+            }
+
+            vec.len += 1;
+        }
+
+        (vec, iter)
+    }
+
+    /// Constructs a new `StackVec<T>` using the partially initialized
+    /// `storage` as the backing store. The first `len` elements of `storage`
+    /// are treated as if they were `push`ed onto `self`, and are dropped along
+    /// with the vector. This is useful when a device has already filled the
+    /// start of a buffer.
+    ///
+    /// # Safety
+    ///
+    /// The first `len` elements of `storage` must be initialized, and must not
+    /// be dropped or used again by the caller.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len > storage.len()`.
+    pub unsafe fn from_uninit_with_len(
+        storage: &'a mut [MaybeUninit<T>],
+        len: usize
+    ) -> StackVec<'a, T> {
+        if len > storage.len() {
+            panic!("StackVec::from_uninit_with_len(): len {} exceeds capacity {}", len, storage.len());
+        }
+
+        StackVec { storage, len }
     }
 
     /// Returns the number of elements this vector can hold.
     pub fn capacity(&self) -> usize {
-        unimplemented!()
+        self.storage.len()
     }
 
-    /// Shortens the vector, keeping the first `len` elements. If `len` is
-    /// greater than the vector's current length, this has no effect. Note that
-    /// this method has no effect on the capacity of the vector.
+    /// Shortens the vector, keeping the first `len` elements and dropping the
+    /// rest. If `len` is greater than the vector's current length, this has no
+    /// effect. Note that this method has no effect on the capacity of the
+    /// vector.
     pub fn truncate(&mut self, len: usize) {
-        unimplemented!()
+        if len >= self.len {
+            return;
+        }
+
+        let tail = &mut self.storage[len..self.len] as *mut [MaybeUninit<T>] as *mut [T];
+        self.len = len;
+        // SAFETY: the tail was initialized and is no longer part of the vector.
+        unsafe { ptr::drop_in_place(tail) }
     }
 
     /// Extracts a slice containing the entire vector, consuming `self`.
     ///
     /// Note that the returned slice's length will be the length of this vector,
-    /// _not_ the length of the original backing storage.
+    /// _not_ the length of the original backing storage. The elements are not
+    /// dropped.
     pub fn into_slice(self) -> &'a mut [T] {
-        unimplemented!()
+        let mut this = mem::ManuallyDrop::new(self);
+        let len = this.len;
+        let storage = mem::replace(&mut this.storage, &mut []);
+        // SAFETY: the first `len` elements are initialized.
+        unsafe { slice::from_raw_parts_mut(storage.as_mut_ptr() as *mut T, len) }
     }
 
     /// Extracts a slice containing the entire vector.
     pub fn as_slice(&self) -> &[T] {
-        unimplemented!()
+        // SAFETY: the first `len` elements are initialized.
+        unsafe { slice::from_raw_parts(self.storage.as_ptr() as *const T, self.len) }
     }
 
     /// Extracts a mutable slice of the entire vector.
     pub fn as_mut_slice(&mut self) -> &mut [T] {
-        unimplemented!()
+        // SAFETY: the first `len` elements are initialized.
+        unsafe { slice::from_raw_parts_mut(self.storage.as_mut_ptr() as *mut T, self.len) }
     }
 
     /// Returns the number of elements in the vector, also referred to as its
     /// 'length'.
     pub fn len(&self) -> usize {
-        unimplemented!()
+        self.len
     }
 
     /// Returns true if the vector contains no elements.
     pub fn is_empty(&self) -> bool {
-        unimplemented!()
+        self.len == 0
     }
 
     /// Returns true if the vector is at capacity.
     pub fn is_full(&self) -> bool {
-        unimplemented!()
+        self.len == self.capacity()
     }
 
     /// Appends `value` to the back of this vector if the vector is not full.
@@ -94,17 +220,389 @@ impl<'a, T: 'a> StackVec<'a, T> {
     /// If this vector is full, an `Err` is returned. Otherwise, `Ok` is
     /// returned.
     pub fn push(&mut self, value: T) -> Result<(), ()> {
-        unimplemented!()
+        if self.is_full() {
+            return Err(());
+        }
+
+        self.storage[self.len] = MaybeUninit::new(value);
+        self.len += 1;
+        Ok(())
     }
-}
 
-impl<'a, T: Clone + 'a> StackVec<'a, T> {
     /// If this vector is not empty, removes the last element from this vector
-    /// by cloning it and returns it. Otherwise returns `None`.
+    /// and returns it. Otherwise returns `None`.
     pub fn pop(&mut self) -> Option<T> {
-        unimplemented!()
+        if self.is_empty() {
+            return None;
+        }
+
+        self.len -= 1;
+        // SAFETY: the element was initialized and is no longer part of the
+        // vector, so it is read out exactly once.
+        Some(unsafe { ptr::read(self.storage[self.len].as_ptr()) })
+    }
+
+    /// Inserts `value` at position `index`, shifting all elements after it to
+    /// the right.
+    ///
+    /// # Error
+    ///
+    /// If this vector is full, an `Err` is returned and `value` is dropped.
+    /// Otherwise, `Ok` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len()`.
+    pub fn insert(&mut self, index: usize, value: T) -> Result<(), ()> {
+        let len = self.len();
+        if index > len {
+            panic!("insert index (is {}) should be <= len (is {})", index, len);
+        }
+
+        self.push(value)?;
+        self.as_mut_slice()[index..].rotate_right(1);
+        Ok(())
+    }
+
+    /// Binary searches this sorted vector with a comparator function. See
+    /// `slice::binary_search_by()`.
+    pub fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        self.as_slice().binary_search_by(f)
+    }
+
+    /// Appends every value yielded by `iter` to the back of this vector, in
+    /// order, until either the iterator is exhausted or the vector is full.
+    ///
+    /// # Error
+    ///
+    /// If the vector fills up before the iterator is exhausted, an `Err` is
+    /// returned. The values pushed before that point remain in the vector, and
+    /// the first value that did not fit is dropped. Otherwise, `Ok` is returned
+    /// with the number of values that were pushed.
+    pub fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<usize, ()> {
+        let mut pushed = 0;
+        for value in iter {
+            self.push(value)?;
+            pushed += 1;
+        }
+
+        Ok(pushed)
+    }
+
+    /// Removes the elements in `range` from the vector, returning them by value
+    /// from an iterator. The remaining elements keep their order and the
+    /// backing storage is reused as is.
+    ///
+    /// The range is removed even if the iterator is not fully consumed; the
+    /// elements that were not yielded are dropped with the iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end, or if the end
+    /// of the range is greater than the length of the vector.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T> {
+        Drain::new(self.storage, &mut self.len, range)
+    }
+
+    /// Retains only the elements for which `f` returns `true`, removing the
+    /// rest. The retained elements keep their relative order. Only the first
+    /// `len()` elements of the backing storage are visited.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        let mut kept = 0;
+        {
+            let slice = self.as_mut_slice();
+            for i in 0..slice.len() {
+                if f(&slice[i]) {
+                    slice.swap(kept, i);
+                    kept += 1;
+                }
+            }
+        }
+
+        self.truncate(kept);
+    }
+
+    /// Removes the element at `index` and returns it, replacing it with the
+    /// last element of the vector. This does not preserve ordering, but is
+    /// O(1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len();
+        if index >= len {
+            panic!("swap_remove index (is {}) should be < len (is {})", index, len);
+        }
+
+        self.as_mut_slice().swap(index, len - 1);
+        self.pop().expect("vector is non-empty")
+    }
+
+    /// Returns the unused tail of the backing storage. Values written to it
+    /// become part of the vector once its length is set with `assume_len()`.
+    ///
+    /// # Safety
+    ///
+    /// A vector created with `new` or `with_len` borrows storage that its
+    /// owner reads as initialized `T`s once the vector is gone. For such a
+    /// vector, the caller must leave every slot of the returned slice
+    /// initialized: values may be written, but no slot may be overwritten
+    /// with `MaybeUninit::uninit()`. Vectors created from uninitialized
+    /// storage have no such restriction.
+    pub unsafe fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        &mut self.storage[self.len..]
+    }
+
+    /// Returns the elements of the vector and the unused tail of the backing
+    /// storage at once. See `spare_capacity_mut()`.
+    ///
+    /// # Safety
+    ///
+    /// The same as for `spare_capacity_mut()`.
+    pub unsafe fn split_at_spare_mut(&mut self) -> (&mut [T], &mut [MaybeUninit<T>]) {
+        let (elements, spare) = self.storage.split_at_mut(self.len);
+        // The first `len` elements are initialized.
+        let elements = &mut *(elements as *mut [MaybeUninit<T>] as *mut [T]);
+        (elements, spare)
+    }
+
+    /// Sets the length of the vector to `len`, typically after writing values
+    /// to `spare_capacity_mut()`. Elements past `len` are not dropped.
+    ///
+    /// # Safety
+    ///
+    /// The first `len` elements of the backing storage must be initialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is greater than the capacity of the vector.
+    pub unsafe fn assume_len(&mut self, len: usize) {
+        if len > self.capacity() {
+            panic!("StackVec::assume_len(): len {} exceeds capacity {}", len, self.capacity());
+        }
+
+        self.len = len;
     }
 }
 
-// FIXME: Implement `Deref`, `DerefMut`, and `IntoIterator` for `StackVec`.
-// FIXME: Implement IntoIterator` for `&StackVec`.
+impl<'a, T: 'a> StackVec<'a, T> {
+    /// Splits the vector into arrays of type `A`, starting at the beginning,
+    /// and a remainder shorter than the array length. Useful for viewing
+    /// buffered data as fixed-size records: `as_chunks::<[u8; 3]>()` yields
+    /// 3-byte records.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `A` is a zero-length array.
+    pub fn as_chunks<A: Array<Item = T>>(&self) -> (&[A], &[T]) {
+        chunks::as_chunks(self.as_slice())
+    }
+
+    /// Mutable version of `as_chunks()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `A` is a zero-length array.
+    pub fn as_chunks_mut<A: Array<Item = T>>(&mut self) -> (&mut [A], &mut [T]) {
+        chunks::as_chunks_mut(self.as_mut_slice())
+    }
+}
+
+impl<'a> StackVec<'a, u8> {
+    /// Views the bytes of this vector as a prefix of bytes, the largest run of
+    /// correctly aligned `U`s, and a suffix of bytes. Unlike
+    /// `slice::align_to()`, this is safe since `U` is `Pod`.
+    ///
+    /// Values are read in native byte order.
+    pub fn align_to<U: Pod>(&self) -> (&[u8], &[U], &[u8]) {
+        chunks::align_to(self.as_slice())
+    }
+
+    /// Mutable version of `align_to()`.
+    pub fn align_to_mut<U: Pod>(&mut self) -> (&mut [u8], &mut [U], &mut [u8]) {
+        chunks::align_to_mut(self.as_mut_slice())
+    }
+}
+
+impl<'a, T: Pod + 'a> StackVec<'a, T> {
+    /// Zeroes the unused tail of the backing storage and passes it to `f`,
+    /// then appends the first `n` values written to it, where `Ok(n)` is
+    /// returned by `f`. This lets a reader fill the vector in place:
+    ///
+    /// ```rust,ignore
+    /// vec.fill_spare(|spare| uart.read(spare))?;
+    /// ```
+    ///
+    /// # Error
+    ///
+    /// If `f` returns an `Err`, the vector is left unchanged and the error is
+    /// returned. Otherwise, `Ok(n)` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the length of the slice passed to `f`.
+    pub fn fill_spare<E, F>(&mut self, f: F) -> Result<usize, E>
+    where
+        F: FnOnce(&mut [T]) -> Result<usize, E>,
+    {
+        // SAFETY: zeroing leaves every spare slot holding a valid `T`.
+        let spare = chunks::zeroed(unsafe { self.spare_capacity_mut() });
+        let available = spare.len();
+        let n = f(spare)?;
+        if n > available {
+            panic!("StackVec::fill_spare(): {} values filled, only {} available", n, available);
+        }
+
+        self.len += n;
+        Ok(n)
+    }
+}
+
+impl<'a, T: PartialEq + 'a> StackVec<'a, T> {
+    /// Removes consecutive repeated elements, keeping the first of each run.
+    /// If the vector is sorted, this removes all duplicates.
+    pub fn dedup(&mut self) {
+        let mut kept = 0;
+        {
+            let slice = self.as_mut_slice();
+            for i in 0..slice.len() {
+                if kept == 0 || slice[i] != slice[kept - 1] {
+                    slice.swap(kept, i);
+                    kept += 1;
+                }
+            }
+        }
+
+        self.truncate(kept);
+    }
+}
+
+impl<'a, T: Ord + 'a> StackVec<'a, T> {
+    /// Inserts `value` into this sorted vector, keeping it sorted. `value` is
+    /// placed after any elements equal to it, so equal elements stay in
+    /// insertion order.
+    ///
+    /// # Error
+    ///
+    /// If this vector is full, an `Err` is returned and `value` is dropped.
+    /// Otherwise, `Ok` is returned with the index `value` was inserted at.
+    pub fn insert_sorted(&mut self, value: T) -> Result<usize, ()> {
+        // Treating equal elements as smaller makes the search always fail,
+        // yielding the index just past the last element equal to `value`.
+        let index = match self.binary_search_by(|element| {
+            match element.cmp(&value) {
+                Ordering::Equal => Ordering::Less,
+                ordering => ordering,
+            }
+        }) {
+            Ok(index) | Err(index) => index,
+        };
+        self.insert(index, value)?;
+        Ok(index)
+    }
+}
+
+impl<'a, T: Clone + 'a> StackVec<'a, T> {
+    /// Appends clones of all of the elements in `other` to the back of this
+    /// vector if they all fit.
+    ///
+    /// # Error
+    ///
+    /// If there is not enough room for all of `other`, an `Err` is returned and
+    /// the vector is left unchanged. Otherwise, `Ok` is returned with the
+    /// number of elements that were copied, `other.len()`.
+    pub fn extend_from_slice(&mut self, other: &[T]) -> Result<usize, ()> {
+        if other.len() > self.capacity() - self.len() {
+            return Err(());
+        }
+
+        for value in other {
+            self.push(value.clone())?;
+        }
+
+        Ok(other.len())
+    }
+}
+
+/// Appends every value yielded by the iterator, like `try_extend()`.
+///
+/// # Panics
+///
+/// Panics if the vector fills up before the iterator is exhausted. Use
+/// `try_extend()` to handle running out of space.
+impl<'a, T: 'a> Extend<T> for StackVec<'a, T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        if self.try_extend(iter).is_err() {
+            panic!("StackVec::extend(): capacity {} exceeded", self.capacity());
+        }
+    }
+}
+
+/// Appends a copy of every value yielded by the iterator, like
+/// `try_extend()`.
+///
+/// # Panics
+///
+/// Panics if the vector fills up before the iterator is exhausted.
+impl<'a, 'b, T: Copy + 'a + 'b> Extend<&'b T> for StackVec<'a, T> {
+    fn extend<I: IntoIterator<Item = &'b T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned())
+    }
+}
+
+impl<'a, T: 'a> Drop for StackVec<'a, T> {
+    fn drop(&mut self) {
+        self.truncate(0);
+    }
+}
+
+impl<'a, T: 'a> Deref for StackVec<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<'a, T: 'a> DerefMut for StackVec<'a, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<'a, T: fmt::Debug + 'a> fmt::Debug for StackVec<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T: 'a> IntoIterator for StackVec<'a, T> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_slice().iter_mut()
+    }
+}
+
+impl<'a, 'b, T: 'a> IntoIterator for &'b StackVec<'a, T> {
+    type Item = &'b T;
+    type IntoIter = slice::Iter<'b, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, 'b, T: 'a> IntoIterator for &'b mut StackVec<'a, T> {
+    type Item = &'b mut T;
+    type IntoIter = slice::IterMut<'b, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
@@ -0,0 +1,129 @@
+use core::fmt;
+use core::ops::Deref;
+use core::str;
+
+use crate::StackVec;
+
+/// A UTF-8 string backed by a byte slice.
+///
+/// `StackString` is to `String` what `StackVec` is to `Vec`: its capacity, in
+/// bytes, is bounded by the user-supplied slice, so appending is fallible.
+/// Contents are only ever appended a whole `char` or `str` at a time, so the
+/// string is always valid UTF-8.
+pub struct StackString<'a> {
+    vec: StackVec<'a, u8>,
+}
+
+impl<'a> StackString<'a> {
+    /// Constructs a new, empty `StackString` using `storage` as the backing
+    /// store. The returned `StackString` will be able to hold `storage.len()`
+    /// bytes.
+    pub fn new(storage: &'a mut [u8]) -> StackString<'a> {
+        StackString { vec: StackVec::new(storage) }
+    }
+
+    /// Returns the number of bytes this string can hold.
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns the length of this string in bytes.
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns true if the string is empty.
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Extracts a string slice containing the entire string.
+    pub fn as_str(&self) -> &str {
+        // SAFETY: only whole `str`s and `char`s are ever appended.
+        unsafe { str::from_utf8_unchecked(self.vec.as_slice()) }
+    }
+
+    /// Returns the bytes of this string.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.vec.as_slice()
+    }
+
+    /// Appends `s` to the end of this string if it fits.
+    ///
+    /// # Error
+    ///
+    /// If there is not enough room for all of `s`, an `Err` is returned and
+    /// the string is left unchanged. Otherwise, `Ok` is returned.
+    pub fn push_str(&mut self, s: &str) -> Result<(), ()> {
+        self.vec.extend_from_slice(s.as_bytes()).map(|_| ())
+    }
+
+    /// Appends the character `c` to the end of this string if it fits.
+    ///
+    /// # Error
+    ///
+    /// If there is not enough room for the UTF-8 encoding of `c`, an `Err` is
+    /// returned and the string is left unchanged. Otherwise, `Ok` is returned.
+    pub fn push_char(&mut self, c: char) -> Result<(), ()> {
+        self.push_str(c.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Removes the last character from this string and returns it, or `None`
+    /// if the string is empty.
+    pub fn pop(&mut self) -> Option<char> {
+        let c = self.as_str().chars().next_back()?;
+        self.vec.truncate(self.len() - c.len_utf8());
+        Some(c)
+    }
+
+    /// Shortens this string to `len` bytes. If `len` is greater than the
+    /// string's current length, this has no effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` does not lie on a `char` boundary.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            if !self.as_str().is_char_boundary(len) {
+                panic!("StackString::truncate(): {} is not a char boundary", len);
+            }
+
+            self.vec.truncate(len);
+        }
+    }
+
+    /// Removes all contents of this string.
+    pub fn clear(&mut self) {
+        self.vec.truncate(0);
+    }
+}
+
+impl<'a> Deref for StackString<'a> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<'a> fmt::Write for StackString<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s).map_err(|_| fmt::Error)
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push_char(c).map_err(|_| fmt::Error)
+    }
+}
+
+impl<'a> fmt::Display for StackString<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<'a> fmt::Debug for StackString<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
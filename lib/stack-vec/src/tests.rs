@@ -1,5 +1,18 @@
+#![allow(clippy::reversed_empty_ranges, clippy::explicit_counter_loop)]
+
+use core::cell::Cell;
+
 use crate::StackVec;
 
+/// A non-`Clone` value that counts how many times it has been dropped.
+struct Counted<'c>(usize, &'c Cell<usize>);
+
+impl<'c> Drop for Counted<'c> {
+    fn drop(&mut self) {
+        self.1.set(self.1.get() + 1);
+    }
+}
+
 #[test]
 fn assignment_text_example() {
     let mut storage = [0u8; 1024];
@@ -220,3 +233,655 @@ fn errors() {
         assert_eq!(vec.pop(), None);
     }
 }
+
+#[test]
+fn extend_from_slice() {
+    let mut storage = [0u8; 5];
+    let mut stack_vec = StackVec::new(&mut storage);
+
+    assert_eq!(stack_vec.extend_from_slice(&[1, 2, 3]), Ok(3));
+    assert_eq!(stack_vec.as_slice(), &[1, 2, 3]);
+
+    assert_eq!(stack_vec.extend_from_slice(&[4, 5, 6]), Err(()));
+    assert_eq!(stack_vec.as_slice(), &[1, 2, 3]);
+
+    assert_eq!(stack_vec.extend_from_slice(&[]), Ok(0));
+    assert_eq!(stack_vec.extend_from_slice(&[4, 5]), Ok(2));
+    assert_eq!(stack_vec.as_slice(), &[1, 2, 3, 4, 5]);
+    assert!(stack_vec.is_full());
+}
+
+#[test]
+fn try_extend() {
+    let mut storage = [0usize; 5];
+    let mut stack_vec = StackVec::new(&mut storage);
+
+    assert_eq!(stack_vec.try_extend(0..3), Ok(3));
+    assert_eq!(stack_vec.as_slice(), &[0, 1, 2]);
+
+    assert_eq!(stack_vec.try_extend(10..20), Err(()));
+    assert_eq!(stack_vec.as_slice(), &[0, 1, 2, 10, 11]);
+    assert!(stack_vec.is_full());
+
+    assert_eq!(stack_vec.try_extend(None), Ok(0));
+}
+
+#[test]
+fn retain() {
+    let mut storage = [0usize; 10];
+    let mut stack_vec = StackVec::new(&mut storage);
+    stack_vec.try_extend(0..8).expect("cap = 10");
+
+    stack_vec.retain(|&x| x % 3 != 0);
+    assert_eq!(stack_vec.as_slice(), &[1, 2, 4, 5, 7]);
+
+    stack_vec.retain(|_| true);
+    assert_eq!(stack_vec.as_slice(), &[1, 2, 4, 5, 7]);
+
+    stack_vec.retain(|_| false);
+    assert!(stack_vec.is_empty());
+    assert_eq!(stack_vec.capacity(), 10);
+}
+
+#[test]
+fn dedup() {
+    let mut storage = [0u8; 10];
+    let mut stack_vec = StackVec::new(&mut storage);
+    stack_vec.dedup();
+    assert!(stack_vec.is_empty());
+
+    stack_vec.extend_from_slice(b"  ls   -l ").expect("cap = 10");
+    stack_vec.dedup();
+    assert_eq!(stack_vec.as_slice(), b" ls -l ");
+
+    stack_vec.truncate(0);
+    stack_vec.extend_from_slice(&[1, 1, 2, 1, 1]).expect("cap = 10");
+    stack_vec.dedup();
+    assert_eq!(stack_vec.as_slice(), &[1, 2, 1]);
+}
+
+#[test]
+fn swap_remove() {
+    let mut storage = [0usize; 5];
+    let mut stack_vec = StackVec::new(&mut storage);
+    stack_vec.try_extend(10..15).expect("cap = 5");
+
+    assert_eq!(stack_vec.swap_remove(1), 11);
+    assert_eq!(stack_vec.as_slice(), &[10, 14, 12, 13]);
+
+    assert_eq!(stack_vec.swap_remove(3), 13);
+    assert_eq!(stack_vec.as_slice(), &[10, 14, 12]);
+
+    assert_eq!(stack_vec.swap_remove(0), 10);
+    assert_eq!(stack_vec.swap_remove(0), 12);
+    assert_eq!(stack_vec.swap_remove(0), 14);
+    assert!(stack_vec.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn swap_remove_oob() {
+    let mut storage = [0usize; 5];
+    let mut stack_vec = StackVec::new(&mut storage);
+    stack_vec.push(1).expect("cap = 5");
+    stack_vec.swap_remove(1);
+}
+
+mod non_clone {
+    use core::cell::Cell;
+    use core::mem::MaybeUninit;
+
+    use super::Counted;
+    use crate::StackVec;
+
+    #[test]
+    fn pop_moves_out() {
+        let drops = Cell::new(0);
+        let mut storage: [MaybeUninit<Counted>; 4] = unsafe { MaybeUninit::uninit().assume_init() };
+        let mut stack_vec = StackVec::from_uninit(&mut storage);
+
+        for i in 0..4 {
+            assert!(stack_vec.push(Counted(i, &drops)).is_ok());
+        }
+
+        let last = stack_vec.pop().expect("len = 4");
+        assert_eq!(last.0, 3);
+        assert_eq!(drops.get(), 0);
+
+        drop(last);
+        assert_eq!(drops.get(), 1);
+        assert_eq!(stack_vec.len(), 3);
+    }
+
+    #[test]
+    fn mutable_references() {
+        let (mut a, mut b) = (1, 2);
+        {
+            let mut storage: [MaybeUninit<&mut i32>; 2] = unsafe { MaybeUninit::uninit().assume_init() };
+            let mut stack_vec = StackVec::from_uninit(&mut storage);
+            stack_vec.push(&mut a).expect("cap = 2");
+            stack_vec.push(&mut b).expect("cap = 2");
+
+            *stack_vec.pop().expect("len = 2") += 10;
+            *stack_vec.pop().expect("len = 1") += 10;
+            assert!(stack_vec.pop().is_none());
+        }
+
+        assert_eq!((a, b), (11, 12));
+    }
+
+    #[test]
+    fn drops_remaining() {
+        let drops = Cell::new(0);
+        let mut storage: [MaybeUninit<Counted>; 4] = unsafe { MaybeUninit::uninit().assume_init() };
+        {
+            let mut stack_vec = StackVec::from_uninit(&mut storage);
+            for i in 0..4 {
+                assert!(stack_vec.push(Counted(i, &drops)).is_ok());
+            }
+
+            stack_vec.truncate(1);
+            assert_eq!(drops.get(), 3);
+        }
+
+        assert_eq!(drops.get(), 4);
+    }
+}
+
+mod uninit {
+    use core::cell::Cell;
+    use core::mem::MaybeUninit;
+
+    use super::Counted;
+    use crate::{uninit_storage, StackVec};
+
+    #[test]
+    fn from_uninit() {
+        let mut storage = uninit_storage::<[usize; 1024]>();
+        let mut stack_vec = StackVec::from_uninit(&mut storage);
+        assert!(stack_vec.is_empty());
+        assert_eq!(stack_vec.capacity(), 1024);
+
+        stack_vec.try_extend(0..1024).expect("cap = 1024");
+        assert!(stack_vec.is_full());
+        assert_eq!(stack_vec[1023], 1023);
+    }
+
+    #[test]
+    fn from_uninit_with_len() {
+        let mut storage = uninit_storage::<[u8; 8]>();
+        storage[0] = MaybeUninit::new(b'h');
+        storage[1] = MaybeUninit::new(b'i');
+
+        let mut stack_vec = unsafe { StackVec::from_uninit_with_len(&mut storage, 2) };
+        stack_vec.push(b'!').expect("cap = 8");
+        assert_eq!(stack_vec.as_slice(), b"hi!");
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_uninit_with_len_too_long() {
+        let mut storage = uninit_storage::<[u8; 8]>();
+        let _ = unsafe { StackVec::from_uninit_with_len(&mut storage, 9) };
+    }
+
+    #[test]
+    fn drops_only_initialized_prefix() {
+        let drops = Cell::new(0);
+        let mut storage = uninit_storage::<[Counted; 16]>();
+        {
+            let mut stack_vec = StackVec::from_uninit(&mut storage);
+            for i in 0..3 {
+                assert!(stack_vec.push(Counted(i, &drops)).is_ok());
+            }
+
+            drop(stack_vec.pop());
+            assert_eq!(drops.get(), 1);
+        }
+
+        assert_eq!(drops.get(), 3);
+    }
+}
+
+mod drain {
+    use core::cell::Cell;
+
+    use super::Counted;
+    use crate::{uninit_storage, StackVec};
+
+    #[test]
+    fn drain_middle() {
+        let mut storage = [0usize; 8];
+        let mut stack_vec = StackVec::new(&mut storage);
+        stack_vec.try_extend(0..8).expect("cap = 8");
+
+        let mut drain = stack_vec.drain(2..5);
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.as_slice(), &[2, 3, 4]);
+        assert_eq!(drain.next(), Some(2));
+        assert_eq!(drain.next_back(), Some(4));
+        assert_eq!(drain.next(), Some(3));
+        assert_eq!(drain.next(), None);
+        drop(drain);
+
+        assert_eq!(stack_vec.as_slice(), &[0, 1, 5, 6, 7]);
+        assert_eq!(stack_vec.capacity(), 8);
+        stack_vec.try_extend(10..13).expect("cap = 8");
+        assert_eq!(stack_vec.as_slice(), &[0, 1, 5, 6, 7, 10, 11, 12]);
+    }
+
+    #[test]
+    fn drain_ranges() {
+        let mut storage = [0usize; 8];
+        let mut stack_vec = StackVec::new(&mut storage);
+        stack_vec.try_extend(0..8).expect("cap = 8");
+
+        assert!(stack_vec.drain(..2).eq(0..2));
+        assert!(stack_vec.drain(4..).eq(6..8));
+        assert!(stack_vec.drain(1..=1).eq(3..4));
+        assert_eq!(stack_vec.drain(0..0).count(), 0);
+        assert_eq!(stack_vec.as_slice(), &[2, 4, 5]);
+
+        assert!(stack_vec.drain(..).eq([2, 4, 5].iter().cloned()));
+        assert!(stack_vec.is_empty());
+    }
+
+    #[test]
+    fn unconsumed_elements_are_dropped() {
+        let drops = Cell::new(0);
+        let mut storage = uninit_storage::<[Counted; 8]>();
+        let mut stack_vec = StackVec::from_uninit(&mut storage);
+        for i in 0..6 {
+            assert!(stack_vec.push(Counted(i, &drops)).is_ok());
+        }
+
+        {
+            let mut drain = stack_vec.drain(1..4);
+            assert_eq!(drain.next().map(|c| c.0), Some(1));
+            assert_eq!(drops.get(), 1);
+        }
+
+        assert_eq!(drops.get(), 3);
+        assert!(stack_vec.iter().map(|c| c.0).eq([0, 4, 5].iter().cloned()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn drain_out_of_bounds() {
+        let mut storage = [0usize; 8];
+        let mut stack_vec = StackVec::new(&mut storage);
+        stack_vec.push(1).expect("cap = 8");
+        stack_vec.drain(0..2);
+    }
+
+    #[test]
+    #[should_panic(expected = "drain end overflows usize")]
+    fn drain_inclusive_end_overflow() {
+        let mut storage = [0usize; 8];
+        let mut stack_vec = StackVec::new(&mut storage);
+        stack_vec.drain(0..=usize::max_value());
+    }
+}
+
+mod array_vec {
+    use core::cell::Cell;
+
+    use super::Counted;
+    use crate::ArrayVec;
+
+    struct Holder {
+        args: ArrayVec<[u8; 4]>,
+    }
+
+    #[test]
+    fn push_pop() {
+        let mut holder = Holder { args: ArrayVec::new() };
+        assert!(holder.args.is_empty());
+        assert_eq!(holder.args.capacity(), 4);
+
+        for i in 0..4 {
+            assert!(holder.args.push(i).is_ok());
+        }
+
+        assert!(holder.args.is_full());
+        assert!(holder.args.push(4).is_err());
+        assert_eq!(holder.args.as_slice(), &[0, 1, 2, 3]);
+        assert_eq!(holder.args.pop(), Some(3));
+        assert_eq!(holder.args.len(), 3);
+        assert_eq!(&holder.args[..], &[0, 1, 2]);
+    }
+
+    #[test]
+    fn same_api_as_stack_vec() {
+        let mut vec: ArrayVec<[u32; 8]> = ArrayVec::default();
+        assert_eq!(vec.try_extend(0..6), Ok(6));
+        vec.retain(|&v| v % 2 == 0);
+        assert_eq!(vec.as_slice(), &[0, 2, 4]);
+
+        assert_eq!(vec.swap_remove(0), 0);
+        assert_eq!(vec.as_slice(), &[4, 2]);
+
+        assert_eq!(vec.extend_from_slice(&[2, 2, 7]), Ok(3));
+        vec.dedup();
+        assert_eq!(vec.as_slice(), &[4, 2, 7]);
+        assert!(vec.extend_from_slice(&[0; 6]).is_err());
+
+        assert!(vec.drain(1..).eq([2, 7].iter().cloned()));
+        assert_eq!(vec.as_slice(), &[4]);
+
+        for v in &mut vec {
+            *v += 1;
+        }
+
+        let copy = vec.clone();
+        vec.clear();
+        assert!(vec.is_empty());
+        assert_eq!(copy.as_slice(), &[5]);
+    }
+
+    #[test]
+    fn drops_elements() {
+        let drops = Cell::new(0);
+        {
+            let mut vec: ArrayVec<[Counted; 4]> = ArrayVec::new();
+            for i in 0..4 {
+                assert!(vec.push(Counted(i, &drops)).is_ok());
+            }
+
+            vec.truncate(3);
+            assert_eq!(drops.get(), 1);
+
+            drop(vec.pop());
+            assert_eq!(drops.get(), 2);
+        }
+
+        assert_eq!(drops.get(), 4);
+    }
+}
+
+mod stack_string {
+    use core::fmt::Write;
+
+    use crate::StackString;
+
+    #[test]
+    fn push_and_pop() {
+        let mut storage = [0u8; 8];
+        let mut string = StackString::new(&mut storage);
+        assert!(string.is_empty());
+        assert_eq!(string.capacity(), 8);
+
+        assert!(string.push_str("ab").is_ok());
+        assert!(string.push_char('é').is_ok());
+        assert!(string.push_char('→').is_ok());
+        assert_eq!(string.as_str(), "abé→");
+        assert_eq!(string.len(), 7);
+
+        // Neither fits whole, so nothing is written.
+        assert!(string.push_char('é').is_err());
+        assert!(string.push_str("cd").is_err());
+        assert_eq!(string.as_str(), "abé→");
+
+        assert_eq!(string.pop(), Some('→'));
+        assert_eq!(string.pop(), Some('é'));
+        assert_eq!(&*string, "ab");
+        string.clear();
+        assert_eq!(string.pop(), None);
+    }
+
+    #[test]
+    fn fmt_write() {
+        let mut storage = [0u8; 16];
+        let mut string = StackString::new(&mut storage);
+        let name = "x";
+        assert!(write!(string, "{}-{:02}", name, 7).is_ok());
+        assert_eq!(string.as_str(), "x-07");
+        assert!(write!(string, "{:>20}", name).is_err());
+    }
+
+    #[test]
+    fn truncate() {
+        let mut storage = [0u8; 8];
+        let mut string = StackString::new(&mut storage);
+        string.push_str("aé").expect("fits");
+        string.truncate(10);
+        assert_eq!(string.as_str(), "aé");
+        string.truncate(1);
+        assert_eq!(string.as_str(), "a");
+    }
+
+    #[test]
+    #[should_panic]
+    fn truncate_mid_char() {
+        let mut storage = [0u8; 8];
+        let mut string = StackString::new(&mut storage);
+        string.push_str("aé").expect("fits");
+        string.truncate(2);
+    }
+}
+
+mod sorted {
+    use crate::{ArrayVec, StackVec};
+
+    #[test]
+    fn insert() {
+        let mut storage = [0u8; 4];
+        let mut stack_vec = StackVec::new(&mut storage);
+        assert!(stack_vec.insert(0, 2).is_ok());
+        assert!(stack_vec.insert(0, 0).is_ok());
+        assert!(stack_vec.insert(1, 1).is_ok());
+        assert!(stack_vec.insert(3, 3).is_ok());
+        assert_eq!(stack_vec.as_slice(), &[0, 1, 2, 3]);
+        assert!(stack_vec.insert(0, 9).is_err());
+        assert_eq!(stack_vec.as_slice(), &[0, 1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_out_of_bounds() {
+        let mut storage = [0u8; 4];
+        let mut stack_vec = StackVec::new(&mut storage);
+        let _ = stack_vec.insert(1, 0);
+    }
+
+    #[test]
+    fn insert_sorted() {
+        let mut storage = [(0u32, 0u8); 6];
+        let mut deadlines = StackVec::new(&mut storage);
+        for &deadline in &[(30, 0), (10, 1), (20, 2), (10, 3), (40, 4)] {
+            assert!(deadlines.insert_sorted(deadline).is_ok());
+        }
+
+        assert_eq!(deadlines.as_slice(), &[(10, 1), (10, 3), (20, 2), (30, 0), (40, 4)]);
+        assert_eq!(deadlines.insert_sorted((5, 5)), Ok(0));
+        assert_eq!(deadlines.insert_sorted((1, 6)), Err(()));
+
+        assert_eq!(deadlines.binary_search_by(|d| d.0.cmp(&20)), Ok(3));
+        assert_eq!(deadlines.binary_search_by(|d| d.0.cmp(&25)), Err(4));
+    }
+
+    #[test]
+    fn array_vec_insert_sorted() {
+        let mut vec: ArrayVec<[i32; 4]> = ArrayVec::new();
+        for &v in &[3, -1, 2, 2] {
+            assert!(vec.insert_sorted(v).is_ok());
+        }
+
+        assert_eq!(vec.as_slice(), &[-1, 2, 2, 3]);
+        assert!(vec.insert_sorted(0).is_err());
+        assert_eq!(vec.binary_search_by(|v| v.cmp(&3)), Ok(3));
+    }
+}
+
+mod extend {
+    use crate::{uninit_storage, ArrayVec, StackVec};
+
+    #[test]
+    fn extend() {
+        let mut storage = [0u8; 8];
+        let mut stack_vec = StackVec::new(&mut storage);
+        stack_vec.extend(0..3);
+        stack_vec.extend(&[7, 8]);
+        stack_vec.extend((0..8).filter(|v| v % 4 == 0).map(|v| v * 10));
+        assert_eq!(stack_vec.as_slice(), &[0, 1, 2, 7, 8, 0, 40]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn extend_overflow() {
+        let mut storage = [0u8; 4];
+        let mut stack_vec = StackVec::new(&mut storage);
+        stack_vec.extend(0..5);
+    }
+
+    #[test]
+    fn from_iter_with_storage() {
+        let mut storage = uninit_storage::<[u32; 4]>();
+        let (vec, mut rest) = StackVec::from_iter_with_storage(&mut storage, 1..7);
+        assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+        assert_eq!(rest.next(), Some(5));
+
+        let mut storage = uninit_storage::<[u32; 4]>();
+        let (vec, mut rest) = StackVec::from_iter_with_storage(&mut storage, 1..3);
+        assert_eq!(vec.as_slice(), &[1, 2]);
+        assert_eq!(rest.next(), None);
+    }
+
+    #[test]
+    fn array_vec_collect() {
+        let vec: ArrayVec<[u8; 4]> = b"abc".iter().cloned().collect();
+        assert_eq!(vec.as_slice(), b"abc");
+
+        let mut vec = vec;
+        vec.extend(b"d".iter().cloned());
+        assert!(vec.is_full());
+    }
+
+    #[test]
+    #[should_panic]
+    fn array_vec_collect_overflow() {
+        let _: ArrayVec<[u8; 2]> = (0..3).collect();
+    }
+}
+
+mod chunks {
+    use crate::{ArrayVec, StackVec};
+
+    #[test]
+    fn as_chunks() {
+        let mut storage = [0u8; 16];
+        let mut stack_vec = StackVec::new(&mut storage);
+        stack_vec.extend(1..=7);
+
+        let (records, rest) = stack_vec.as_chunks::<[u8; 3]>();
+        assert_eq!(records, &[[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(rest, &[7]);
+
+        let (records, rest) = stack_vec.as_chunks_mut::<[u8; 2]>();
+        records[1] = [0xAA, 0xBB];
+        rest[0] = 0xCC;
+        assert_eq!(stack_vec.as_slice(), &[1, 2, 0xAA, 0xBB, 5, 6, 0xCC]);
+
+        let vec: ArrayVec<[u8; 4]> = (0..4).collect();
+        assert_eq!(vec.as_chunks::<[u8; 4]>(), (&[[0, 1, 2, 3]][..], &[][..]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn as_chunks_zero() {
+        let mut storage = [0u8; 4];
+        let stack_vec = StackVec::new(&mut storage);
+        stack_vec.as_chunks::<[u8; 0]>();
+    }
+
+    #[test]
+    fn align_to() {
+        let mut storage = [0u32; 4];
+        let bytes: &mut [u8] = unsafe { storage.align_to_mut().1 };
+        let mut stack_vec = StackVec::new(bytes);
+        stack_vec.extend(&[1, 0, 0, 0, 2, 0, 0, 0, 3]);
+
+        let (prefix, words, suffix) = stack_vec.align_to::<u32>();
+        assert!(prefix.is_empty());
+        assert_eq!(words, &[u32::from_ne_bytes([1, 0, 0, 0]), u32::from_ne_bytes([2, 0, 0, 0])]);
+        assert_eq!(suffix, &[3]);
+
+        let (_, words, _) = stack_vec.align_to_mut::<[u8; 2]>();
+        assert_eq!(words.len(), 4);
+        words[0] = [9, 9];
+        assert_eq!(&stack_vec[..2], &[9, 9]);
+    }
+}
+
+mod spare {
+    use core::mem::MaybeUninit;
+
+    use crate::{uninit_storage, ArrayVec, StackVec};
+
+    #[test]
+    fn spare_capacity_mut() {
+        let mut storage = uninit_storage::<[u8; 8]>();
+        let mut stack_vec = StackVec::from_uninit(&mut storage);
+        stack_vec.extend(&[1, 2, 3]);
+
+        let spare = unsafe { stack_vec.spare_capacity_mut() };
+        assert_eq!(spare.len(), 5);
+        spare[0] = MaybeUninit::new(4);
+        spare[1] = MaybeUninit::new(5);
+        unsafe { stack_vec.assume_len(5) };
+        assert_eq!(stack_vec.as_slice(), &[1, 2, 3, 4, 5]);
+
+        let (elements, spare) = unsafe { stack_vec.split_at_spare_mut() };
+        elements[0] = 9;
+        spare[0] = MaybeUninit::new(6);
+        unsafe { stack_vec.assume_len(6) };
+        assert_eq!(stack_vec.as_slice(), &[9, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn assume_len_beyond_capacity() {
+        let mut storage = [0u8; 4];
+        let mut stack_vec = StackVec::new(&mut storage);
+        unsafe { stack_vec.assume_len(5) };
+    }
+
+    #[test]
+    fn fill_spare() {
+        let mut storage = uninit_storage::<[u8; 8]>();
+        let mut stack_vec = StackVec::from_uninit(&mut storage);
+        stack_vec.push(1).unwrap();
+
+        let filled = stack_vec.fill_spare(|spare| -> Result<usize, ()> {
+            assert_eq!(spare, &[0; 7]);
+            spare[..3].copy_from_slice(&[2, 3, 4]);
+            Ok(3)
+        });
+        assert_eq!(filled, Ok(3));
+        assert_eq!(stack_vec.as_slice(), &[1, 2, 3, 4]);
+
+        let failed = stack_vec.fill_spare(|spare| {
+            spare[0] = 0xFF;
+            Err("read failed")
+        });
+        assert_eq!(failed, Err("read failed"));
+        assert_eq!(stack_vec.as_slice(), &[1, 2, 3, 4]);
+
+        let mut vec: ArrayVec<[u32; 4]> = ArrayVec::new();
+        vec.push(7).unwrap();
+        assert_eq!(vec.fill_spare(|spare| -> Result<usize, ()> {
+            spare[0] = 8;
+            Ok(1)
+        }), Ok(1));
+        assert_eq!(vec.as_slice(), &[7, 8]);
+        assert_eq!(vec.spare_capacity_mut().len(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn fill_spare_overfilled() {
+        let mut storage = [0u8; 4];
+        let mut stack_vec = StackVec::new(&mut storage);
+        let _ = stack_vec.fill_spare(|_| -> Result<usize, ()> { Ok(5) });
+    }
+}
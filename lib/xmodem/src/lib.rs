@@ -2,27 +2,35 @@
 
 #![feature(decl_macro)]
 
-use shim::io;
+use shim::io::{self, Write};
 use shim::ioerr;
 
 #[cfg(test)] mod tests;
+mod machine;
 mod read_ext;
 mod progress;
+mod text;
 
+pub use machine::{Action, Expect, Machine};
 pub use progress::{Progress, ProgressFn};
+pub use text::{LineEnding, Mode};
 
 use read_ext::ReadExt;
+use text::{StripCr, TextWriter};
 
 const SOH: u8 = 0x01;
 const EOT: u8 = 0x04;
 const ACK: u8 = 0x06;
 const NAK: u8 = 0x15;
 const CAN: u8 = 0x18;
+/// Sent in place of the first packet to announce a text-mode transfer.
+const TXT: u8 = b'T';
 
 /// Implementation of the XMODEM protocol.
+///
+/// This is a blocking driver for the sans-io protocol core in [`Machine`].
 pub struct Xmodem<R> {
-    packet: u8,
-    started: bool,
+    machine: Machine,
     inner: R,
     progress: ProgressFn
 }
@@ -48,15 +56,44 @@ impl Xmodem<()> {
     /// the transmission. See the [`Progress`] enum for more information.
     ///
     /// Returns the number of bytes written to `to`, excluding padding zeroes.
-    pub fn transmit_with_progress<R, W>(mut data: R, to: W, f: ProgressFn) -> io::Result<usize>
+    pub fn transmit_with_progress<R, W>(data: R, to: W, f: ProgressFn) -> io::Result<usize>
+        where W: io::Read + io::Write, R: io::Read
+    {
+        Xmodem::transmit_padded(data, to, None, f)
+    }
+
+    /// Transmits `data` to the receiver `to` using the XMODEM protocol in
+    /// transfer mode `mode`. See [`Mode`] for how each mode treats the data.
+    /// A text-mode transfer fails if the receiver does not accept text mode.
+    ///
+    /// The function `f` is used as a callback to indicate progress throughout
+    /// the transmission. See the [`Progress`] enum for more information.
+    ///
+    /// Returns the number of bytes written to `to`, excluding padding and, in
+    /// text mode, any stripped `\r`s.
+    pub fn transmit_with_mode<R, W>(data: R, to: W, mode: Mode, f: ProgressFn) -> io::Result<usize>
+        where W: io::Read + io::Write, R: io::Read
+    {
+        match mode {
+            Mode::Binary => Xmodem::transmit_padded(data, to, None, f),
+            Mode::Text(_) => Xmodem::transmit_padded(StripCr::new(data), to, Some(text::SUB), f),
+        }
+    }
+
+    /// Transmits `data` to `to`. If `text_pad` is `Some`, text mode is
+    /// announced and the last packet is padded with it; otherwise the last
+    /// packet is padded with zeroes.
+    fn transmit_padded<R, W>(mut data: R, to: W, text_pad: Option<u8>, f: ProgressFn) -> io::Result<usize>
         where W: io::Read + io::Write, R: io::Read
     {
         let mut transmitter = Xmodem::new_with_progress(to, f);
+        transmitter.machine.set_text_mode(text_pad.is_some());
+        let pad = text_pad.unwrap_or(0);
         let mut packet = [0u8; 128];
         let mut written = 0;
         'next_packet: loop {
             let n = data.read_max(&mut packet)?;
-            packet[n..].iter_mut().for_each(|b| *b = 0);
+            packet[n..].iter_mut().for_each(|b| *b = pad);
 
             if n == 0 {
                 transmitter.write_packet(&[])?;
@@ -92,20 +129,46 @@ impl Xmodem<()> {
     ///
     /// The function `f` is used as a callback to indicate progress throughout
     /// the reception. See the [`Progress`] enum for more information.
-    pub fn receive_with_progress<R, W>(from: R, mut into: W, f: ProgressFn) -> io::Result<usize>
+    pub fn receive_with_progress<R, W>(from: R, into: W, f: ProgressFn) -> io::Result<usize>
+       where R: io::Read + io::Write, W: io::Write
+    {
+        Xmodem::receive_with_mode(from, into, Mode::Binary, f)
+    }
+
+    /// Receives `data` from `from` using the XMODEM protocol in transfer mode
+    /// `mode` and writes it into `into`. See [`Mode`] for how each mode treats
+    /// the data. In text mode, a sender that does not announce text mode is
+    /// received in binary mode. In binary mode, a sender that announces text
+    /// mode is refused.
+    ///
+    /// The function `f` is used as a callback to indicate progress throughout
+    /// the reception. See the [`Progress`] enum for more information.
+    ///
+    /// Returns the number of bytes written to `into`. For a binary transfer,
+    /// this is the number of bytes read from `from`, a multiple of 128.
+    pub fn receive_with_mode<R, W>(from: R, into: W, mode: Mode, f: ProgressFn) -> io::Result<usize>
        where R: io::Read + io::Write, W: io::Write
     {
         let mut receiver = Xmodem::new_with_progress(from, f);
+        let ending = match mode {
+            Mode::Binary => None,
+            Mode::Text(ending) => Some(ending),
+        };
+        receiver.machine.set_text_mode(ending.is_some());
+        let mut into = TextWriter::new(into, ending);
+
         let mut packet = [0u8; 128];
-        let mut received = 0;
         'next_packet: loop {
             for _ in 0..10 {
                 match receiver.read_packet(&mut packet) {
                     Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
                     Err(e) => return Err(e),
                     Ok(0) => break 'next_packet,
-                    Ok(n) => {
-                        received += n;
+                    Ok(_) => {
+                        if !receiver.machine.text() {
+                            into.set_binary();
+                        }
+
                         into.write_all(&packet)?;
                         continue 'next_packet;
                     }
@@ -115,12 +178,12 @@ impl Xmodem<()> {
             return ioerr!(BrokenPipe, "bad receive");
         }
 
-        Ok(received)
+        Ok(into.written())
     }
 }
 
 fn get_checksum(buf: &[u8]) -> u8 {
-    return buf.iter().fold(0, |a, b| a.wrapping_add(*b));
+    buf.iter().fold(0, |a, b| a.wrapping_add(*b))
 }
 
 impl<T: io::Read + io::Write> Xmodem<T> {
@@ -128,7 +191,7 @@ impl<T: io::Read + io::Write> Xmodem<T> {
     /// `inner`. The returned instance can be used for both receiving
     /// (downloading) and sending (uploading).
     pub fn new(inner: T) -> Self {
-        Xmodem { packet: 1, started: false, inner, progress: progress::noop}
+        Xmodem { machine: Machine::new(), inner, progress: progress::noop }
     }
 
     /// Returns a new `Xmodem` instance with the internal reader/writer set to
@@ -137,7 +200,7 @@ impl<T: io::Read + io::Write> Xmodem<T> {
     /// callback to indicate progress throughout the transfer. See the
     /// [`Progress`] enum for more information.
     pub fn new_with_progress(inner: T, f: ProgressFn) -> Self {
-        Xmodem { packet: 1, started: false, inner, progress: f }
+        Xmodem { machine: Machine::new(), inner, progress: f }
     }
 
     /// Reads a single byte from the inner I/O stream. If `abort_on_can` is
@@ -182,7 +245,16 @@ impl<T: io::Read + io::Write> Xmodem<T> {
     /// byte was not `byte`, if the read byte was `CAN` and `byte` is not `CAN`,
     /// or if writing the `CAN` byte failed on byte mismatch.
     fn expect_byte_or_cancel(&mut self, byte: u8, expected: &'static str) -> io::Result<u8> {
-        unimplemented!()
+        let read = self.read_byte(false)?;
+        if read == byte {
+            return Ok(read);
+        }
+
+        self.write_byte(CAN)?;
+        match read {
+            CAN => ioerr!(ConnectionAborted, "received CAN"),
+            _ => ioerr!(InvalidData, expected),
+        }
     }
 
     /// Reads a single byte from the inner I/O stream and compares it to `byte`.
@@ -197,7 +269,44 @@ impl<T: io::Read + io::Write> Xmodem<T> {
     /// of `ConnectionAborted` is returned. Otherwise, the error kind is
     /// `InvalidData`.
     fn expect_byte(&mut self, byte: u8, expected: &'static str) -> io::Result<u8> {
-        unimplemented!()
+        let read = self.read_byte(false)?;
+        if read == byte {
+            return Ok(read);
+        }
+
+        match read {
+            CAN => ioerr!(ConnectionAborted, "received CAN"),
+            _ => ioerr!(InvalidData, expected),
+        }
+    }
+
+    /// Carries out the actions of `self.machine` against the inner stream
+    /// until the current operation completes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading or writing to the inner stream fails, or if
+    /// the operation fails.
+    fn drive(&mut self) -> io::Result<usize> {
+        loop {
+            match self.machine.next_action() {
+                Action::Write(byte) => self.write_byte(byte)?,
+                Action::Read => {
+                    let byte = match self.machine.expecting() {
+                        Some(Expect { byte, msg, cancel: true }) => {
+                            self.expect_byte_or_cancel(byte, msg)?
+                        }
+                        Some(Expect { byte, msg, cancel: false }) => self.expect_byte(byte, msg)?,
+                        None => self.read_byte(false)?,
+                    };
+                    self.machine.push_byte(byte);
+                }
+                Action::Progress(progress) => (self.progress)(progress),
+                Action::Done(n) => return Ok(n),
+                Action::Fail(kind, msg) => return Err(io::Error::new(kind, msg)),
+                Action::Idle => return Ok(0),
+            }
+        }
     }
 
     /// Reads (downloads) a single packet from the inner stream using the XMODEM
@@ -224,7 +333,14 @@ impl<T: io::Read + io::Write> Xmodem<T> {
     ///
     /// An error of kind `UnexpectedEof` is returned if `buf.len() < 128`.
     pub fn read_packet(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        unimplemented!()
+        if buf.len() < 128 {
+            return ioerr!(UnexpectedEof, "buffer smaller than 128 bytes");
+        }
+
+        self.machine.begin_receive();
+        let n = self.drive()?;
+        buf[..n].copy_from_slice(&self.machine.packet()[..n]);
+        Ok(n)
     }
 
     /// Sends (uploads) a single packet to the inner stream using the XMODEM
@@ -258,7 +374,8 @@ impl<T: io::Read + io::Write> Xmodem<T> {
     ///
     /// An error of kind `Interrupted` is returned if a packet checksum fails.
     pub fn write_packet(&mut self, buf: &[u8]) -> io::Result<usize> {
-        unimplemented!()
+        self.machine.begin_transmit(buf);
+        self.drive()
     }
 
     /// Flush this output stream, ensuring that all intermediately buffered
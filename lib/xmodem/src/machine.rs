@@ -0,0 +1,435 @@
+use shim::io;
+
+use crate::progress::Progress;
+use crate::{get_checksum, ACK, CAN, EOT, NAK, SOH, TXT};
+
+/// The next thing the driver of a [`Machine`] must do.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Action {
+    /// Write the byte `.0` to the peer.
+    Write(u8),
+    /// Read a byte from the peer and pass it to [`Machine::push_byte()`].
+    Read,
+    /// Report progress `.0` to the user.
+    Progress(Progress),
+    /// The current operation completed successfully, transferring `.0` bytes
+    /// of packet data.
+    Done(usize),
+    /// The current operation failed with an error of kind `.0`.
+    Fail(io::ErrorKind, &'static str),
+    /// No operation is in progress.
+    Idle,
+}
+
+/// The only byte a pending `Action::Read` may yield for the operation to
+/// continue. See [`Machine::expecting()`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Expect {
+    /// The expected byte.
+    pub byte: u8,
+    /// The error message if any other byte is read.
+    pub msg: &'static str,
+    /// Whether `CAN` is sent to the peer if any other byte is read.
+    pub cancel: bool,
+}
+
+/// Internal protocol state.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum State {
+    /// No operation in progress, or the queue holds the final actions.
+    Idle,
+    /// Waiting for the sender's first `SOH` or `EOT`, or its `TXT`
+    /// announcing text mode.
+    RxStart,
+    /// Waiting for the sender's `SOH` or `EOT`.
+    RxControl,
+    /// Waiting for the packet number.
+    RxPacketNumber,
+    /// Waiting for the one's complement of the packet number.
+    RxPacketInverse,
+    /// Waiting for data byte `.0` of the packet.
+    RxData(usize),
+    /// Waiting for the packet checksum.
+    RxChecksum,
+    /// Waiting for the sender's second `EOT`.
+    RxSecondEot,
+    /// Waiting for the receiver's initial `NAK`.
+    TxStartNak,
+    /// Waiting for the receiver's `ACK` to `TXT`.
+    TxTextAck,
+    /// Sending byte `.0` of the framed packet.
+    TxPacket(usize),
+    /// Waiting for the receiver to acknowledge the packet.
+    TxAck,
+    /// Waiting for the receiver's `NAK` to the first `EOT`.
+    TxEotNak,
+    /// Waiting for the receiver's `ACK` to the second `EOT`.
+    TxEotAck,
+}
+
+/// Size of a framed packet: `SOH`, number, inverse, 128 data bytes, checksum.
+const FRAME_LEN: usize = 3 + 128 + 1;
+
+/// Maximum number of queued actions. No transition queues more than three.
+const QUEUE_LEN: usize = 4;
+
+/// The XMODEM protocol as a sans-io state machine.
+///
+/// A `Machine` performs no I/O itself. An operation is started with
+/// [`begin_receive()`] or [`begin_transmit()`], after which the driver
+/// repeatedly calls [`next_action()`] and carries out the returned
+/// [`Action`]: writing a byte, reading a byte and feeding it back with
+/// [`push_byte()`], or reporting progress. The operation is over once `Done`
+/// or `Fail` is returned. [`Xmodem`] is the blocking driver for
+/// `io::Read + io::Write` streams.
+///
+/// [`begin_receive()`]: Machine::begin_receive
+/// [`begin_transmit()`]: Machine::begin_transmit
+/// [`next_action()`]: Machine::next_action
+/// [`push_byte()`]: Machine::push_byte
+/// [`Xmodem`]: crate::Xmodem
+pub struct Machine {
+    packet: u8,
+    started: bool,
+    text_mode: bool,
+    text: bool,
+    state: State,
+    eot: bool,
+    buf: [u8; 128],
+    checksum: u8,
+    queue: [Option<Action>; QUEUE_LEN],
+    queue_head: usize,
+    queue_len: usize,
+}
+
+impl Default for Machine {
+    fn default() -> Machine {
+        Machine::new()
+    }
+}
+
+impl Machine {
+    /// Returns a new, idle `Machine`.
+    pub fn new() -> Machine {
+        Machine {
+            packet: 1,
+            started: false,
+            text_mode: false,
+            text: false,
+            state: State::Idle,
+            eot: false,
+            buf: [0; 128],
+            checksum: 0,
+            queue: [None; QUEUE_LEN],
+            queue_head: 0,
+            queue_len: 0,
+        }
+    }
+
+    /// Sets whether transfers started from now on may use text mode.
+    ///
+    /// A transmitter with text mode set announces it by sending `TXT` after
+    /// the receiver's initial `NAK`, and starts sending once the receiver
+    /// acknowledges it. A receiver with text mode set acknowledges the
+    /// announcement; one without cancels the transfer. Whether the current
+    /// transfer uses text mode is returned by [`text()`](Machine::text).
+    pub fn set_text_mode(&mut self, text_mode: bool) {
+        self.text_mode = text_mode;
+    }
+
+    /// Returns `true` if the sender announced text mode for the current
+    /// transfer.
+    pub fn text(&self) -> bool {
+        self.text
+    }
+
+    /// Starts receiving (downloading) a single packet. The operation completes
+    /// with `Done(128)` once a packet was received, after which its data is
+    /// available from [`packet()`](Machine::packet), or with `Done(0)` if the
+    /// sender ended the transmission.
+    ///
+    /// The first packet of a transfer sends the initial `NAK` and reports
+    /// `Progress::Started`. It also accepts the sender's text mode
+    /// announcement; see [`set_text_mode()`](Machine::set_text_mode). A
+    /// received packet is reported with `Progress::Packet`.
+    ///
+    /// The operation fails with `Interrupted` if the packet checksum is wrong,
+    /// with `ConnectionAborted` if an unexpected `CAN` is received, and with
+    /// `InvalidData` on any other protocol error.
+    pub fn begin_receive(&mut self) {
+        self.clear_queue();
+        if self.started {
+            self.state = State::RxControl;
+        } else {
+            self.started = true;
+            self.text = false;
+            self.push_action(Action::Write(NAK));
+            self.push_action(Action::Progress(Progress::Started));
+            self.state = State::RxStart;
+        }
+    }
+
+    /// Starts transmitting (uploading) the first 128 bytes of `data` as a
+    /// single packet, or end of transmission if `data` is empty. The operation
+    /// completes with `Done(128)` once the packet was acknowledged, or with
+    /// `Done(0)` once end of transmission was acknowledged.
+    ///
+    /// The first packet of a transfer reports `Progress::Waiting`, waits for
+    /// the receiver's `NAK`, then reports `Progress::Started`. In text mode,
+    /// it then announces text mode and waits for the receiver's `ACK`. A sent
+    /// packet is reported with `Progress::Packet`.
+    ///
+    /// The operation fails with `UnexpectedEof` if `data` is non-empty but
+    /// shorter than 128 bytes, with `Interrupted` if the receiver rejects the
+    /// packet, with `ConnectionAborted` if an unexpected `CAN` is received, and
+    /// with `InvalidData` on any other protocol error.
+    pub fn begin_transmit(&mut self, data: &[u8]) {
+        self.clear_queue();
+        if !data.is_empty() && data.len() < 128 {
+            self.fail(io::ErrorKind::UnexpectedEof, "packet smaller than 128 bytes");
+            return;
+        }
+
+        self.eot = data.is_empty();
+        if !self.eot {
+            self.buf.copy_from_slice(&data[..128]);
+            self.checksum = get_checksum(&self.buf);
+        }
+
+        if self.started {
+            self.start_sending();
+        } else {
+            self.text = false;
+            self.push_action(Action::Progress(Progress::Waiting));
+            self.state = State::TxStartNak;
+        }
+    }
+
+    /// Returns the data of the most recently received packet.
+    pub fn packet(&self) -> &[u8; 128] {
+        &self.buf
+    }
+
+    /// Returns the byte the pending `Action::Read` must yield, if exactly one
+    /// byte lets the operation continue. A driver may use this to check the
+    /// byte itself and fail early; any byte passed to
+    /// [`push_byte()`](Machine::push_byte) is still handled correctly.
+    pub fn expecting(&self) -> Option<Expect> {
+        if self.queue_len != 0 {
+            return None;
+        }
+
+        let (byte, msg, cancel) = match self.state {
+            State::RxPacketNumber => (self.packet, "expected packet number", true),
+            State::RxPacketInverse => (255 - self.packet, "expected inverse packet number", true),
+            State::RxSecondEot => (EOT, "expected second EOT", true),
+            State::TxStartNak => (NAK, "expected NAK to start", false),
+            State::TxTextAck => (ACK, "expected ACK to TXT", false),
+            State::TxEotNak => (NAK, "expected NAK to first EOT", false),
+            State::TxEotAck => (ACK, "expected ACK to second EOT", false),
+            _ => return None,
+        };
+
+        Some(Expect { byte, msg, cancel })
+    }
+
+    /// Returns the next action the driver must perform.
+    pub fn next_action(&mut self) -> Action {
+        if let Some(action) = self.pop_action() {
+            return action;
+        }
+
+        match self.state {
+            State::Idle => Action::Idle,
+            State::TxPacket(i) => {
+                let byte = match i {
+                    0 => SOH,
+                    1 => self.packet,
+                    2 => 255 - self.packet,
+                    _ if i == FRAME_LEN - 1 => self.checksum,
+                    _ => self.buf[i - 3],
+                };
+
+                self.state = match i + 1 {
+                    FRAME_LEN => State::TxAck,
+                    next => State::TxPacket(next),
+                };
+
+                Action::Write(byte)
+            }
+            _ => Action::Read,
+        }
+    }
+
+    /// Feeds a byte read from the peer into the machine. Must only be called
+    /// in response to `Action::Read`; bytes pushed at any other time are
+    /// ignored.
+    pub fn push_byte(&mut self, byte: u8) {
+        if self.queue_len != 0 {
+            return;
+        }
+
+        if let Some(expect) = self.expecting() {
+            if byte != expect.byte {
+                if expect.cancel {
+                    self.cancel_unexpected(byte, expect.msg);
+                } else {
+                    self.unexpected(byte, expect.msg);
+                }
+
+                return;
+            }
+        }
+
+        // Bytes other than the one `expecting()` reports never get here.
+        match self.state {
+            State::Idle | State::TxPacket(_) => {}
+            State::RxStart => match byte {
+                TXT if self.text_mode => {
+                    self.text = true;
+                    self.push_action(Action::Write(ACK));
+                    self.state = State::RxControl;
+                }
+                TXT => self.cancel(io::ErrorKind::InvalidData, "sender requested text mode"),
+                _ => {
+                    self.state = State::RxControl;
+                    self.push_byte(byte);
+                }
+            },
+            State::RxControl => match byte {
+                SOH => self.state = State::RxPacketNumber,
+                EOT => {
+                    self.push_action(Action::Write(NAK));
+                    self.state = State::RxSecondEot;
+                }
+                CAN => self.fail(io::ErrorKind::ConnectionAborted, "received CAN"),
+                _ => self.cancel(io::ErrorKind::InvalidData, "expected SOH or EOT"),
+            },
+            State::RxPacketNumber => self.state = State::RxPacketInverse,
+            State::RxPacketInverse => {
+                self.checksum = 0;
+                self.state = State::RxData(0);
+            }
+            State::RxData(i) => {
+                self.buf[i] = byte;
+                self.checksum = self.checksum.wrapping_add(byte);
+                self.state = match i + 1 {
+                    128 => State::RxChecksum,
+                    next => State::RxData(next),
+                };
+            }
+            State::RxChecksum => {
+                if byte == self.checksum {
+                    self.push_action(Action::Write(ACK));
+                    self.packet_done();
+                } else {
+                    self.push_action(Action::Write(NAK));
+                    self.fail(io::ErrorKind::Interrupted, "checksum failed");
+                }
+            }
+            State::RxSecondEot => {
+                self.push_action(Action::Write(ACK));
+                self.transfer_done();
+            }
+            State::TxStartNak => {
+                self.started = true;
+                self.push_action(Action::Progress(Progress::Started));
+                if self.text_mode {
+                    self.push_action(Action::Write(TXT));
+                    self.state = State::TxTextAck;
+                } else {
+                    self.start_sending();
+                }
+            }
+            State::TxTextAck => {
+                self.text = true;
+                self.start_sending();
+            }
+            State::TxAck => match byte {
+                ACK => self.packet_done(),
+                NAK => self.fail(io::ErrorKind::Interrupted, "receiver NAKed packet"),
+                _ => self.unexpected(byte, "expected ACK or NAK"),
+            },
+            State::TxEotNak => {
+                self.push_action(Action::Write(EOT));
+                self.state = State::TxEotAck;
+            }
+            State::TxEotAck => self.transfer_done(),
+        }
+    }
+
+    /// Queues the first step of sending the current packet or EOT.
+    fn start_sending(&mut self) {
+        if self.eot {
+            self.push_action(Action::Write(EOT));
+            self.state = State::TxEotNak;
+        } else {
+            self.state = State::TxPacket(0);
+        }
+    }
+
+    /// Completes a successful packet transfer.
+    fn packet_done(&mut self) {
+        self.push_action(Action::Progress(Progress::Packet(self.packet)));
+        self.push_action(Action::Done(128));
+        self.packet = self.packet.wrapping_add(1);
+        self.state = State::Idle;
+    }
+
+    /// Completes the transfer, readying the machine for a new one.
+    fn transfer_done(&mut self) {
+        self.push_action(Action::Done(0));
+        self.packet = 1;
+        self.started = false;
+        self.state = State::Idle;
+    }
+
+    /// Fails the current operation with an error of kind `kind`.
+    fn fail(&mut self, kind: io::ErrorKind, msg: &'static str) {
+        self.push_action(Action::Fail(kind, msg));
+        self.state = State::Idle;
+    }
+
+    /// Fails the current operation after an unexpected `byte`: with
+    /// `ConnectionAborted` if it was `CAN`, with `InvalidData` otherwise.
+    fn unexpected(&mut self, byte: u8, expected: &'static str) {
+        match byte {
+            CAN => self.fail(io::ErrorKind::ConnectionAborted, "received CAN"),
+            _ => self.fail(io::ErrorKind::InvalidData, expected),
+        }
+    }
+
+    /// Like `unexpected`, but first sends `CAN` to the peer.
+    fn cancel_unexpected(&mut self, byte: u8, expected: &'static str) {
+        self.push_action(Action::Write(CAN));
+        self.unexpected(byte, expected);
+    }
+
+    /// Sends `CAN` to the peer and fails with an error of kind `kind`.
+    fn cancel(&mut self, kind: io::ErrorKind, msg: &'static str) {
+        self.push_action(Action::Write(CAN));
+        self.fail(kind, msg);
+    }
+
+    fn clear_queue(&mut self) {
+        self.queue_head = 0;
+        self.queue_len = 0;
+    }
+
+    fn push_action(&mut self, action: Action) {
+        let index = (self.queue_head + self.queue_len) % QUEUE_LEN;
+        self.queue[index] = Some(action);
+        self.queue_len += 1;
+    }
+
+    fn pop_action(&mut self) -> Option<Action> {
+        if self.queue_len == 0 {
+            return None;
+        }
+
+        let action = self.queue[self.queue_head].take();
+        self.queue_head = (self.queue_head + 1) % QUEUE_LEN;
+        self.queue_len -= 1;
+        action
+    }
+}
@@ -4,7 +4,7 @@
 /// methods like [`Xmodem::transmit_with_progress()`],
 /// [`Xmodem::receive_with_progress()`], and [`Xmodem::new_with_progress()`]. It
 /// is intended to be used by progress indicators or for debugging purposes.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Progress {
     /// Waiting for receiver to send NAK.
     Waiting,
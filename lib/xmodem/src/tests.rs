@@ -227,3 +227,184 @@ fn test_eot() {
 
     assert_eq!(&buffer[..], &[NAK, EOT, NAK, EOT, ACK]);
 }
+
+#[test]
+fn test_machine_receive() {
+    let data = [7u8; 128];
+    let checksum = get_checksum(&data);
+
+    let mut machine = Machine::new();
+    machine.begin_receive();
+    assert_eq!(machine.next_action(), Action::Write(NAK));
+    assert_eq!(machine.next_action(), Action::Progress(Progress::Started));
+
+    let header = [SOH, 1, 255 - 1];
+    for &byte in header.iter().chain(data.iter()).chain(Some(checksum).iter()) {
+        assert_eq!(machine.next_action(), Action::Read);
+        machine.push_byte(byte);
+    }
+
+    assert_eq!(machine.next_action(), Action::Write(ACK));
+    assert_eq!(machine.next_action(), Action::Progress(Progress::Packet(1)));
+    assert_eq!(machine.next_action(), Action::Done(128));
+    assert_eq!(&machine.packet()[..], &data[..]);
+    assert_eq!(machine.next_action(), Action::Idle);
+}
+
+#[test]
+fn test_machine_transmit_eot() {
+    let mut machine = Machine::new();
+    machine.begin_transmit(&[]);
+    assert_eq!(machine.next_action(), Action::Progress(Progress::Waiting));
+    assert_eq!(machine.next_action(), Action::Read);
+    machine.push_byte(NAK);
+    assert_eq!(machine.next_action(), Action::Progress(Progress::Started));
+    assert_eq!(machine.next_action(), Action::Write(EOT));
+    assert_eq!(machine.next_action(), Action::Read);
+    machine.push_byte(NAK);
+    assert_eq!(machine.next_action(), Action::Write(EOT));
+    assert_eq!(machine.next_action(), Action::Read);
+    machine.push_byte(ACK);
+    assert_eq!(machine.next_action(), Action::Done(0));
+}
+
+#[test]
+fn test_machine_bad_checksum() {
+    let mut machine = Machine::new();
+    machine.begin_receive();
+    machine.next_action();
+    machine.next_action();
+
+    for &byte in [SOH, 1, 255 - 1].iter().chain([1u8; 128].iter()) {
+        machine.push_byte(byte);
+    }
+
+    machine.push_byte(0);
+    assert_eq!(machine.next_action(), Action::Write(NAK));
+    match machine.next_action() {
+        Action::Fail(kind, _) => assert_eq!(kind, io::ErrorKind::Interrupted),
+        action => panic!("expected failure, got {:?}", action),
+    }
+}
+
+fn text_loop(input: &'static [u8], ending: LineEnding) -> (usize, usize, Vec<u8>) {
+    let (tx, rx) = pipe();
+    let mode = Mode::Text(ending);
+    let tx_thread = std::thread::spawn(move || {
+        Xmodem::transmit_with_mode(input, rx, mode, progress::noop)
+    });
+    let rx_thread = std::thread::spawn(move || {
+        let mut output = vec![];
+        Xmodem::receive_with_mode(tx, &mut output, mode, progress::noop).map(|n| (n, output))
+    });
+
+    let sent = tx_thread.join().expect("tx join okay").expect("tx okay");
+    let (received, output) = rx_thread.join().expect("rx join okay").expect("rx okay");
+    (sent, received, output)
+}
+
+#[test]
+fn test_text_mode() {
+    let (sent, received, output) = text_loop(b"echo hi\r\nls\r\n", LineEnding::Lf);
+    assert_eq!(sent, 11);
+    assert_eq!(received, 11);
+    assert_eq!(&output[..], b"echo hi\nls\n");
+
+    let (_, received, output) = text_loop(b"a\nb\r\n", LineEnding::CrLf);
+    assert_eq!(received, 6);
+    assert_eq!(&output[..], b"a\r\nb\r\n");
+}
+
+#[test]
+fn test_text_mode_keeps_inner_sub() {
+    let mut input = vec![b'x'; 130];
+    input[127] = text::SUB;
+    let input: &'static [u8] = Box::leak(input.into_boxed_slice());
+
+    let (sent, received, output) = text_loop(input, LineEnding::Lf);
+    assert_eq!(sent, 130);
+    assert_eq!(received, 130);
+    assert_eq!(&output[..], input);
+}
+
+#[test]
+fn test_binary_mode() {
+    let (tx, rx) = pipe();
+    let tx_thread = std::thread::spawn(move || {
+        Xmodem::transmit_with_mode(&b"a\r\n"[..], rx, Mode::Binary, progress::noop)
+    });
+
+    let mut output = vec![];
+    let received = Xmodem::receive_with_mode(tx, &mut output, Mode::Binary, progress::noop)
+        .expect("rx okay");
+
+    assert_eq!(tx_thread.join().expect("tx join okay").expect("tx okay"), 3);
+    assert_eq!(received, 128);
+    assert_eq!(&output[..3], b"a\r\n");
+    assert!(output[3..].iter().all(|&b| b == 0));
+}
+
+#[test]
+fn test_text_receiver_accepts_binary_sender() {
+    let (tx, rx) = pipe();
+    let tx_thread = std::thread::spawn(move || {
+        Xmodem::transmit_with_mode(&b"a\r\n"[..], rx, Mode::Binary, progress::noop)
+    });
+
+    let mut output = vec![];
+    let mode = Mode::Text(LineEnding::CrLf);
+    let received = Xmodem::receive_with_mode(tx, &mut output, mode, progress::noop)
+        .expect("rx okay");
+
+    assert_eq!(tx_thread.join().expect("tx join okay").expect("tx okay"), 3);
+    assert_eq!(received, 128);
+    assert_eq!(&output[..3], b"a\r\n");
+    assert!(output[3..].iter().all(|&b| b == 0));
+}
+
+#[test]
+fn test_binary_receiver_refuses_text_sender() {
+    let (tx, rx) = pipe();
+    let tx_thread = std::thread::spawn(move || {
+        let mode = Mode::Text(LineEnding::Lf);
+        Xmodem::transmit_with_mode(&b"a\r\n"[..], rx, mode, progress::noop)
+    });
+
+    let mut output = vec![];
+    let e = Xmodem::receive_with_mode(tx, &mut output, Mode::Binary, progress::noop)
+        .expect_err("rx refuses text");
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+
+    let e = tx_thread.join().expect("tx join okay").expect_err("tx sees CAN");
+    assert_eq!(e.kind(), io::ErrorKind::ConnectionAborted);
+    assert!(output.is_empty());
+}
+
+#[test]
+fn test_machine_text_handshake() {
+    let mut machine = Machine::new();
+    machine.set_text_mode(true);
+    machine.begin_transmit(&[0; 128]);
+    assert_eq!(machine.next_action(), Action::Progress(Progress::Waiting));
+    assert_eq!(machine.next_action(), Action::Read);
+    machine.push_byte(NAK);
+    assert_eq!(machine.next_action(), Action::Progress(Progress::Started));
+    assert_eq!(machine.next_action(), Action::Write(TXT));
+    assert_eq!(machine.next_action(), Action::Read);
+    assert!(!machine.text());
+    machine.push_byte(ACK);
+    assert!(machine.text());
+    assert_eq!(machine.next_action(), Action::Write(SOH));
+
+    let mut machine = Machine::new();
+    machine.set_text_mode(true);
+    machine.begin_receive();
+    assert_eq!(machine.next_action(), Action::Write(NAK));
+    assert_eq!(machine.next_action(), Action::Progress(Progress::Started));
+    machine.push_byte(TXT);
+    assert_eq!(machine.next_action(), Action::Write(ACK));
+    assert!(machine.text());
+    assert_eq!(machine.next_action(), Action::Read);
+    machine.push_byte(SOH);
+    assert_eq!(machine.expecting().map(|e| e.byte), Some(1));
+}
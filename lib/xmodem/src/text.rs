@@ -0,0 +1,150 @@
+use shim::io;
+
+/// The byte used to pad the last packet of a text-mode transfer (`^Z`).
+pub(crate) const SUB: u8 = 0x1A;
+
+/// How the data of a transfer is interpreted.
+///
+/// A text-mode sender announces text mode to the receiver before the first
+/// packet, and a text-mode receiver falls back to binary mode if the sender
+/// does not. A binary-mode receiver refuses a text-mode sender.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Data is transferred unchanged. The last packet is padded with zeroes,
+    /// which the receiver keeps.
+    Binary,
+    /// Data is text. The sender strips every `\r` and pads the last packet
+    /// with `^Z`. The receiver drops the trailing `^Z` padding and writes each
+    /// `\n` with the given line ending.
+    Text(LineEnding),
+}
+
+/// The line ending a text-mode receiver writes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`
+    Lf,
+    /// `\r\n`
+    CrLf,
+}
+
+impl LineEnding {
+    /// Returns the line ending of the platform this crate is compiled for.
+    pub fn native() -> LineEnding {
+        if cfg!(windows) {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    fn as_bytes(self) -> &'static [u8] {
+        match self {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+        }
+    }
+}
+
+/// A reader that yields the bytes of `R` with every `\r` removed.
+pub(crate) struct StripCr<R> {
+    inner: R,
+}
+
+impl<R: io::Read> StripCr<R> {
+    pub(crate) fn new(inner: R) -> StripCr<R> {
+        StripCr { inner }
+    }
+}
+
+impl<R: io::Read> io::Read for StripCr<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let n = self.inner.read(buf)?;
+            if n == 0 {
+                return Ok(0);
+            }
+
+            let mut kept = 0;
+            for i in 0..n {
+                if buf[i] != b'\r' {
+                    buf[kept] = buf[i];
+                    kept += 1;
+                }
+            }
+
+            // A read of only `\r`s is not end of file; try again.
+            if kept != 0 {
+                return Ok(kept);
+            }
+        }
+    }
+}
+
+/// A writer that converts received text for `W`: each `\n` is written with
+/// `ending`, and `^Z`s are held back until a later byte shows they were data
+/// rather than padding. Held back `^Z`s are discarded if no such byte comes.
+/// With no `ending`, bytes are written unchanged.
+pub(crate) struct TextWriter<W> {
+    inner: W,
+    ending: Option<LineEnding>,
+    pending_sub: usize,
+    written: usize,
+}
+
+impl<W: io::Write> TextWriter<W> {
+    pub(crate) fn new(inner: W, ending: Option<LineEnding>) -> TextWriter<W> {
+        TextWriter { inner, ending, pending_sub: 0, written: 0 }
+    }
+
+    /// Writes bytes unchanged from now on.
+    pub(crate) fn set_binary(&mut self) {
+        self.ending = None;
+    }
+
+    /// Returns the number of bytes written to the inner writer.
+    pub(crate) fn written(&self) -> usize {
+        self.written
+    }
+
+    fn write_inner(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.inner.write_all(bytes)?;
+        self.written += bytes.len();
+        Ok(())
+    }
+}
+
+impl<W: io::Write> io::Write for TextWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let ending = match self.ending {
+            Some(ending) => ending,
+            None => {
+                self.write_inner(buf)?;
+                return Ok(buf.len());
+            }
+        };
+
+        for &byte in buf {
+            if byte == SUB {
+                self.pending_sub += 1;
+                continue;
+            }
+
+            while self.pending_sub > 0 {
+                self.write_inner(&[SUB])?;
+                self.pending_sub -= 1;
+            }
+
+            match byte {
+                b'\n' => self.write_inner(ending.as_bytes())?,
+                _ => self.write_inner(&[byte])?,
+            }
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}